@@ -0,0 +1,84 @@
+use std::{fs, path::Path};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use elvis::{
+    planner::{mv::MvPlanner, rm::RmPlanner, traits::Planner},
+    vfs::real::RealFs,
+};
+use tempfile::TempDir;
+
+/// Builds `dirs` top-level directories, each holding `dirs` subdirectories
+/// of `files` small files, like a build output tree
+fn generate(dirs: usize, files: usize) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("target");
+    for top in 0..dirs {
+        for sub in 0..dirs {
+            let leaf = root
+                .join(format!("crate-{}", top))
+                .join(format!("obj-{}", sub));
+            fs::create_dir_all(&leaf).unwrap();
+            for file in 0..files {
+                fs::write(leaf.join(format!("unit-{}.o", file)), b"obj").unwrap();
+            }
+        }
+    }
+    dir
+}
+
+fn backends() -> [(&'static str, RealFs); 2] {
+    [
+        ("sequential", RealFs::default()),
+        ("parallel", RealFs::parallel()),
+    ]
+}
+
+fn plan_rm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("plan_rm_recursive");
+    group.sample_size(10);
+
+    for (dirs, files) in [(10, 20), (20, 25)] {
+        let tree = generate(dirs, files);
+        let target = tree.path().join("target");
+        let size = dirs * dirs * files;
+
+        for (name, fs) in backends() {
+            group.bench_with_input(BenchmarkId::new(name, size), &fs, |b, fs| {
+                b.iter(|| plan_rm_once(&target, tree.path(), fs))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn plan_mv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("plan_mv_tree");
+    group.sample_size(10);
+
+    let tree = generate(20, 25);
+    let source = tree.path().join("target");
+    let dest = tree.path().join("moved");
+
+    for (name, fs) in backends() {
+        group.bench_with_input(BenchmarkId::new(name, 10_000), &fs, |b, fs| {
+            b.iter(|| {
+                MvPlanner::new(
+                    vec![source.clone()],
+                    dest.clone(),
+                    false,
+                    tree.path().to_path_buf(),
+                )
+                .plan(fs)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn plan_rm_once(target: &Path, cwd: &Path, fs: &RealFs) {
+    let plan = RmPlanner::new(vec![target.to_path_buf()], true, false, cwd.to_path_buf()).plan(fs);
+    assert!(plan.errors.is_empty());
+}
+
+criterion_group!(benches, plan_rm, plan_mv);
+criterion_main!(benches);
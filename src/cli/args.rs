@@ -1,13 +1,26 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    planner::{
+        mv::{BackupMode, UpdateMode},
+        policy::FailOn,
+    },
+    printer::options::SortKey,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "elvis")]
 #[command(author, version, about="File-system command preview tool", long_about = None)]
+#[command(arg_required_else_help = true)]
 pub struct Cli {
     #[command(subcommand)]
-    pub command: Command,
+    pub command: Option<Command>,
+
+    #[doc = "Preview a coreutils command line as-is, e.g. `elvis -- rm -rf build/`"]
+    #[arg(last = true, value_name = "COMMAND")]
+    pub wrapped: Vec<String>,
 
     #[doc = "Disable colored output"]
     #[arg(long)]
@@ -21,34 +34,164 @@ pub struct Cli {
     #[arg(short, long)]
     pub max_entries: Option<usize>,
 
+    #[doc = "Sort entries within each section"]
+    #[arg(long, value_enum, default_value_t = Sort::Plan)]
+    pub sort: Sort,
+
+    #[doc = "Reverse the sort order"]
+    #[arg(long)]
+    pub reverse: bool,
+
+    #[doc = "Show the plan as a directory tree, collapsing fully affected directories"]
+    #[arg(long)]
+    pub tree: bool,
+
+    #[doc = "Show the largest files, their ages and a breakdown by extension"]
+    #[arg(long)]
+    pub stats: bool,
+
+    #[doc = "Print the plan as JSON instead of the pretty preview"]
+    #[arg(long)]
+    pub json: bool,
+
+    #[doc = "Apply the plan to an in-memory copy of the tree and report failures instead of executing"]
+    #[arg(long)]
+    pub simulate: bool,
+
+    #[doc = "Only plan and check the plan, never execute; see the exit codes in the README"]
+    #[arg(long, visible_alias = "plan-only")]
+    pub check: bool,
+
+    #[doc = "Reject plans with errors only, or also with warnings"]
+    #[arg(long, value_enum, value_name = "LEVEL", default_value_t = Severity::Errors)]
+    pub fail_on: Severity,
+
+    #[doc = "Reject plans with more than N actions"]
+    #[arg(long, value_name = "N")]
+    pub max_actions: Option<usize>,
+
+    #[doc = "Reject plans deleting, moving or copying more than N bytes"]
+    #[arg(long, value_name = "N")]
+    pub max_bytes: Option<u64>,
+
+    #[doc = "Stream the plan to a temporary file and only keep a preview in memory (rm only)"]
+    #[arg(long, conflicts_with = "simulate")]
+    pub stream: bool,
+
+    #[doc = "Expand glob patterns in paths, even where the shell left a match"]
+    #[arg(long)]
+    pub glob: bool,
+
     #[doc = "Skip confirmation and execute immediately after preview"]
     #[arg(short = 'y', long)]
     pub yes: bool,
+
+    #[doc = "Keep applying actions that do not depend on a failed one"]
+    #[arg(long)]
+    pub keep_going: bool,
+
+    #[doc = "Apply up to N independent actions in parallel"]
+    #[arg(short, long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+
+    #[doc = "Hide the progress bar and progress log lines while executing"]
+    #[arg(long)]
+    pub no_progress: bool,
+
+    #[doc = "Append a JSON record of each executed plan to FILE, or send it to syslog with `syslog`"]
+    #[arg(long, value_name = "FILE", env = "ELVIS_AUDIT_LOG")]
+    pub audit_log: Option<PathBuf>,
+
+    #[doc = "Answer the confirmation prompt without a terminal"]
+    #[arg(long, value_enum, conflicts_with = "yes")]
+    pub answer: Option<Answer>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Answer {
+    Yes,
+    No,
+}
+
+#[doc = "Values of `--fail-on`, see [`FailOn`]"]
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Severity {
+    Errors,
+    Warnings,
+}
+
+impl From<Severity> for FailOn {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Errors => FailOn::Errors,
+            Severity::Warnings => FailOn::Warnings,
+        }
+    }
+}
+
+#[doc = "Values of `--sort`, see [`SortKey`]"]
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Sort {
+    /// Keep the order in which the planner produced the actions
+    Plan,
+    Path,
+    Size,
+    Mtime,
+}
+
+impl From<Sort> for SortKey {
+    fn from(sort: Sort) -> Self {
+        match sort {
+            Sort::Plan => SortKey::Plan,
+            Sort::Path => SortKey::Path,
+            Sort::Size => SortKey::Size,
+            Sort::Mtime => SortKey::Mtime,
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
     #[doc = "Preview file creation or timestamp updates"]
     Touch {
-        #[arg(required = true)]
+        #[arg(required_unless_present = "from_file")]
         targets: Vec<PathBuf>,
+
+        #[command(flatten)]
+        input: InputArgs,
     },
 
     #[doc = "Preview moving or renaming files/directories"]
     Mv {
-        #[arg(required = true)]
-        sources: Vec<PathBuf>,
+        #[doc = "Sources followed by the target, unless --target-directory is given"]
+        #[arg(value_name = "PATH", required_unless_present = "from_file")]
+        paths: Vec<PathBuf>,
 
-        #[arg(required = true)]
-        target: PathBuf,
+        #[doc = "Move all sources into DIR"]
+        #[arg(short = 't', long, value_name = "DIR")]
+        target_directory: Option<PathBuf>,
 
-        #[arg(short, long)]
+        #[doc = "Treat the target as a plain destination, never as a directory to move into"]
+        #[arg(short = 'T', long, conflicts_with = "target_directory")]
+        no_target_directory: bool,
+
+        #[doc = "Do not prompt before overwriting; the last of -f and -n wins"]
+        #[arg(short, long, overrides_with = "no_clobber")]
         force: bool,
+
+        #[command(flatten)]
+        overwrite: OverwriteArgs,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        #[command(flatten)]
+        input: InputArgs,
     },
 
     #[doc = "Preview the deletion of files/directories"]
     Rm {
-        #[arg(required = true)]
+        #[arg(required_unless_present = "from_file")]
         targets: Vec<PathBuf>,
 
         #[arg(short, long)]
@@ -56,5 +199,104 @@ pub enum Command {
 
         #[arg(short, long)]
         force: bool,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        #[command(flatten)]
+        input: InputArgs,
     },
+
+    #[doc = "Preview the combined effect of a shell script of file commands"]
+    Script {
+        #[doc = "Script with touch/mkdir/cp/mv/rm/ln commands, one per line"]
+        script: PathBuf,
+    },
+
+    #[doc = "List executed plans recorded in the audit log (see --audit-log)"]
+    History {
+        #[command(subcommand)]
+        show: Option<HistoryCommand>,
+
+        #[doc = "Only list runs that touched PATH, something below it or one of its parents"]
+        #[arg(long, value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    #[doc = "Show the plan and outcome of one recorded run"]
+    Show {
+        #[doc = "Run number, as listed by `elvis history`"]
+        id: usize,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct OverwriteArgs {
+    #[doc = "Rename destinations that would be overwritten; CONTROL defaults to $VERSION_CONTROL or `existing`"]
+    #[arg(
+        short = 'b',
+        long,
+        value_enum,
+        value_name = "CONTROL",
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    pub backup: Option<Option<BackupMode>>,
+
+    #[doc = "Suffix of simple backups, `~` or $SIMPLE_BACKUP_SUFFIX by default; implies --backup"]
+    #[arg(short = 'S', long, value_name = "SUFFIX")]
+    pub suffix: Option<String>,
+
+    #[doc = "Never overwrite an existing destination, skip the entry instead"]
+    #[arg(short = 'n', long, overrides_with = "force")]
+    pub no_clobber: bool,
+
+    #[doc = "Which existing destinations to replace; MODE defaults to `older`"]
+    #[arg(
+        short = 'u',
+        long,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "older"
+    )]
+    pub update: Option<UpdateMode>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct InputArgs {
+    #[doc = "Read additional paths from FILE, or stdin when FILE is `-`"]
+    #[arg(long, value_name = "FILE")]
+    pub from_file: Option<PathBuf>,
+
+    #[doc = "Paths in --from-file are NUL-separated instead of newline-separated"]
+    #[arg(short = '0', long, requires = "from_file")]
+    pub null: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FilterArgs {
+    #[doc = "Only affect files matching GLOB when walking directories"]
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    #[doc = "Keep entries matching GLOB when walking directories"]
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    #[doc = "Read exclude globs from FILE, one per line"]
+    #[arg(long, value_name = "FILE")]
+    pub exclude_from: Option<PathBuf>,
+
+    #[doc = "Keep entries ignored by .gitignore/.ignore files"]
+    #[arg(long, conflicts_with = "gitignored_only")]
+    pub respect_gitignore: bool,
+
+    #[doc = "Only affect entries ignored by .gitignore/.ignore files"]
+    #[arg(long)]
+    pub gitignored_only: bool,
 }
@@ -0,0 +1,51 @@
+use std::{io, path::PathBuf};
+
+use glob::MatchOptions;
+
+use crate::planner::plan::PatternMatch;
+
+/// Characters that make an argument a glob pattern
+const GLOB_CHARS: [char; 3] = ['*', '?', '['];
+
+/// Expands glob patterns among `paths` in place, including recursive `**`.
+///
+/// Like in the shell, wildcards do not match a leading dot, so `*` leaves
+/// `.git` and `.env` alone; `.*` still matches them.
+///
+/// Without `force`, only arguments that contain glob characters and do not
+/// name an existing file are expanded, which is what the shell leaves behind
+/// for quoted or unmatched patterns. With `force`, every pattern is expanded.
+pub fn expand(paths: &mut Vec<PathBuf>, force: bool) -> io::Result<Vec<PatternMatch>> {
+    let mut expanded = Vec::with_capacity(paths.len());
+    let mut patterns = Vec::new();
+
+    for path in paths.drain(..) {
+        let Some(pattern) = path.to_str().filter(|p| p.contains(GLOB_CHARS)) else {
+            expanded.push(path);
+            continue;
+        };
+
+        if !force && path.symlink_metadata().is_ok() {
+            expanded.push(path);
+            continue;
+        }
+
+        let options = MatchOptions {
+            require_literal_leading_dot: true,
+            ..MatchOptions::new()
+        };
+        let matches: Vec<PathBuf> = glob::glob_with(pattern, options)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+            .filter_map(Result::ok)
+            .collect();
+
+        expanded.extend(matches.iter().cloned());
+        patterns.push(PatternMatch {
+            pattern: pattern.to_string(),
+            matches,
+        });
+    }
+
+    *paths = expanded;
+    Ok(patterns)
+}
@@ -0,0 +1,64 @@
+use std::{io, path::Path};
+
+use crate::{
+    error::Error,
+    executor::audit::AuditLog,
+    printer::{history::HistoryPrinter, options::PrinterOptions},
+};
+
+use super::args::HistoryCommand;
+
+/// Lists or shows runs from the audit log; ids are 1-based line numbers,
+/// which stay stable since the log is only appended to
+pub fn run(
+    log: Option<AuditLog>,
+    show: Option<HistoryCommand>,
+    path: Option<&Path>,
+    options: &PrinterOptions,
+) -> Result<(), Error> {
+    let Some(log) = log else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "No audit log to read (use --audit-log or ELVIS_AUDIT_LOG)",
+        )
+        .into());
+    };
+    let history = log.read()?;
+
+    if let Some(HistoryCommand::Show { id }) = show {
+        if let Some((_, err)) = history.invalid.iter().find(|(line, _)| *line == id) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Recorded run #{} cannot be read: {}", id, err),
+            )
+            .into());
+        }
+        let (_, record) = history
+            .records
+            .iter()
+            .find(|(line, _)| *line == id)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("No recorded run #{}", id))
+            })?;
+        HistoryPrinter::print_record(id, record, options);
+        return Ok(());
+    }
+
+    for (line, err) in history.invalid.iter() {
+        eprintln!(
+            "Warning: Skipped run #{}, which cannot be read: {}",
+            line, err
+        );
+    }
+
+    // Recorded paths are absolute, so resolve the filter the same way
+    let path = path.map(|path| options.cwd.join(path));
+    let runs: Vec<_> = history
+        .records
+        .iter()
+        .map(|(line, record)| (*line, record))
+        .filter(|(_, record)| path.as_ref().is_none_or(|path| record.touches(path)))
+        .collect();
+    HistoryPrinter::print_list(&runs, options);
+    Ok(())
+}
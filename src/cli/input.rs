@@ -0,0 +1,55 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::cli::args::InputArgs;
+
+/// Appends the paths listed by `--from-file` to the ones given as arguments.
+///
+/// Returns whether stdin was read, in which case it can no longer be used
+/// to answer the confirmation prompt.
+pub fn collect_paths(paths: &mut Vec<PathBuf>, input: &InputArgs) -> io::Result<bool> {
+    let Some(source) = &input.from_file else {
+        return Ok(false);
+    };
+
+    let from_stdin = source == Path::new("-");
+    let bytes = if from_stdin {
+        let mut buf = Vec::new();
+        io::stdin().lock().read_to_end(&mut buf)?;
+        buf
+    } else {
+        fs::read(source)?
+    };
+
+    let separator = if input.null { b'\0' } else { b'\n' };
+    for entry in bytes.split(|b| *b == separator) {
+        let entry = if input.null {
+            entry
+        } else {
+            entry.strip_suffix(b"\r").unwrap_or(entry)
+        };
+
+        if !entry.is_empty() {
+            paths.push(path_from_bytes(entry)?);
+        }
+    }
+
+    Ok(from_stdin)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    Ok(PathBuf::from(OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    String::from_utf8(bytes.to_vec())
+        .map(PathBuf::from)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -1,48 +1,346 @@
-use std::{env, io};
+use std::{env, fs, io, path::PathBuf};
+
+use clap::ValueEnum;
 
 use crate::{
-    executor::{execution::Executor, options::ExecutorOptions},
-    planner::{mv::MvPlanner, rm::RmPlanner, touch::TouchPlanner, traits::Planner},
-    printer::{options::PrinterOptions, pretty::PrettyPrinter},
+    error::Error,
+    executor::{
+        audit::{AuditLog, AuditSink},
+        execution::Executor,
+        options::ExecutorOptions,
+        report::ExecutionReport,
+    },
+    planner::{
+        filter::PathFilter,
+        gitignore::GitignoreMode,
+        mv::{BackupMode, MvPlanner, UpdateMode},
+        plan::{CommandKind, PatternMatch, Plan, PlanWarning, WarningKind},
+        policy::Policy,
+        rm::RmPlanner,
+        script::ScriptPlanner,
+        stream::{PlanBuilder, PlanSpool, StreamingPlanner},
+        touch::TouchPlanner,
+        traits::Planner,
+    },
+    printer::{
+        json::JsonPrinter, options::PrinterOptions, pretty::PrettyPrinter,
+        progress::ProgressPrinter, report::ReportPrinter, simulation::SimulationPrinter,
+    },
+    vfs::real::RealFs,
 };
 
-use self::args::{Cli, Command};
+use self::args::{Answer, Cli, Command, FilterArgs, OverwriteArgs};
 
 pub mod args;
+pub mod glob;
+pub mod history;
+pub mod input;
+pub mod wrapper;
 
-pub fn run(cli: Cli) -> io::Result<()> {
+pub fn run(mut cli: Cli) -> Result<(), Error> {
     let cwd = env::current_dir()?;
 
-    let planner: Box<dyn Planner> = match cli.command {
-        Command::Touch { targets } => Box::new(TouchPlanner::new(targets, cwd.clone())),
+    let stdin_consumed;
+    let patterns;
+
+    let command = match cli.command.take() {
+        Some(command) => command,
+        None => wrapper::parse(&cli.wrapped)?,
+    };
+
+    if cli.stream && !matches!(command, Command::Rm { .. }) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--stream is only supported for rm",
+        )
+        .into());
+    }
+    if cli.stream && cli.jobs > 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--stream applies actions one at a time and cannot be combined with --jobs",
+        )
+        .into());
+    }
+
+    let planner: Box<dyn Planner> = match command {
+        Command::Touch { mut targets, input } => {
+            // Touch creates paths, so only expand patterns when asked to
+            patterns = if cli.glob {
+                glob::expand(&mut targets, true)?
+            } else {
+                Vec::new()
+            };
+            stdin_consumed = input::collect_paths(&mut targets, &input)?;
+            Box::new(TouchPlanner::new(targets, cwd.clone()))
+        }
         Command::Mv {
-            sources,
-            target,
+            mut paths,
+            target_directory,
+            no_target_directory,
             force,
-        } => Box::new(MvPlanner::new(sources, target, force, cwd.clone())),
+            overwrite,
+            filter,
+            input,
+        } => {
+            let target = match target_directory {
+                Some(dir) => dir,
+                None => paths.pop().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "Missing move target")
+                })?,
+            };
+
+            let mut sources = paths;
+            patterns = glob::expand(&mut sources, cli.glob)?;
+            stdin_consumed = input::collect_paths(&mut sources, &input)?;
+            if sources.is_empty() && patterns.is_empty() {
+                return Err(
+                    io::Error::new(io::ErrorKind::InvalidInput, "Missing move source").into(),
+                );
+            }
+
+            Box::new(
+                MvPlanner::new(sources, target, force, cwd.clone())
+                    .with_filter(path_filter(filter)?)
+                    .with_no_target_directory(no_target_directory)
+                    .with_backup(backup_mode(&overwrite)?, backup_suffix(&overwrite))
+                    .with_update(update_mode(&overwrite)),
+            )
+        }
         Command::Rm {
-            targets,
+            mut targets,
             recursive,
             force,
-        } => Box::new(RmPlanner::new(targets, recursive, force, cwd.clone())),
+            filter,
+            input,
+        } => {
+            patterns = glob::expand(&mut targets, cli.glob)?;
+            stdin_consumed = input::collect_paths(&mut targets, &input)?;
+            let planner = RmPlanner::new(targets, recursive, force, cwd.clone())
+                .with_filter(path_filter(filter)?);
+            if cli.stream {
+                return run_streamed(&cli, &planner, patterns, stdin_consumed, cwd);
+            }
+            Box::new(planner)
+        }
+        Command::Script { script } => {
+            patterns = Vec::new();
+            stdin_consumed = false;
+            Box::new(ScriptPlanner::new(fs::read_to_string(script)?, cwd.clone()))
+        }
+        Command::History { show, path } => {
+            let options = printer_options(&cli, cwd);
+            return history::run(audit_log(&cli), show, path.as_deref(), &options);
+        }
     };
 
-    let plan = planner.plan();
+    let mut plan = planner.plan(&RealFs::parallel());
+    add_patterns(&mut plan, patterns);
+
+    let printer_opts = printer_options(&cli, cwd);
+
+    let simulation = cli
+        .simulate
+        .then(|| Executor::simulate(&plan, &RealFs::default()));
+
+    if cli.json {
+        JsonPrinter::print(&plan, simulation.as_ref(), &printer_opts)?;
+    } else {
+        PrettyPrinter::print(&plan, &printer_opts);
+        if let Some(simulation) = &simulation {
+            SimulationPrinter::print(simulation, plan.actions.len(), &printer_opts);
+        }
+    }
+
+    if let Some(simulation) = &simulation
+        && !simulation.is_ok()
+    {
+        return Err(Error::PlanInvalid("Simulated execution failed".into()));
+    }
+
+    policy(&cli).check(&plan)?;
+    if cli.check {
+        return checked(&plan);
+    }
+    if simulation.is_some() {
+        return Ok(());
+    }
+
+    let exec_opts = executor_options(&cli, stdin_consumed);
+    let mut progress = ProgressPrinter::new(!cli.no_progress);
+    let report = Executor::execute_with_progress(&plan, &exec_opts, &mut progress)?;
+    finish(&cli, report, &plan, &printer_opts)
+}
+
+/// Plans and executes rm through a spool, so only a preview of the actions
+/// is held in memory
+fn run_streamed(
+    cli: &Cli,
+    planner: &RmPlanner,
+    patterns: Vec<PatternMatch>,
+    stdin_consumed: bool,
+    cwd: PathBuf,
+) -> Result<(), Error> {
+    let printer_opts = printer_options(cli, cwd.clone());
+    let fs = RealFs::default();
+    let mut spool = PlanSpool::collect(
+        PlanBuilder::new(&fs, CommandKind::Rm, cwd),
+        planner.stream(&fs),
+        printer_opts.max_entries,
+    )?;
+    add_patterns(&mut spool.plan, patterns);
+
+    if cli.json {
+        JsonPrinter::print(&spool.plan, None, &printer_opts)?;
+    } else {
+        PrettyPrinter::print(&spool.plan, &printer_opts);
+    }
+
+    policy(cli).check(&spool.plan)?;
+    if cli.check {
+        return checked(&spool.plan);
+    }
+
+    let exec_opts = executor_options(cli, stdin_consumed);
+    let mut progress = ProgressPrinter::new(!cli.no_progress);
+    let report = Executor::execute_spooled(&spool, &exec_opts, &mut progress)?;
+    finish(cli, report, &spool.plan, &printer_opts)
+}
+
+/// Reports failed actions, and an audit log failure next to them
+fn finish(
+    cli: &Cli,
+    report: ExecutionReport,
+    plan: &Plan,
+    printer_opts: &PrinterOptions,
+) -> Result<(), Error> {
+    if !report.is_success() {
+        if !cli.json {
+            ReportPrinter::print(&report, plan, printer_opts);
+        }
+        if let Some(err) = &report.audit_error {
+            eprintln!("Warning: The audit log failed: {}", err);
+        }
+    }
+    report.into_error().map_or(Ok(()), Err)
+}
+
+/// Warns about patterns that matched nothing and records all of them
+fn add_patterns(plan: &mut Plan, patterns: Vec<PatternMatch>) {
+    for pattern in patterns.iter().filter(|p| p.matches.is_empty()) {
+        plan.add_warning(PlanWarning {
+            kind: WarningKind::EmptyPattern,
+            paths: vec![PathBuf::from(&pattern.pattern)],
+            message: format!("Pattern `{}` matched nothing", pattern.pattern),
+        });
+    }
+    plan.patterns = patterns;
+}
 
-    let printer_opts = PrinterOptions {
+fn printer_options(cli: &Cli, cwd: PathBuf) -> PrinterOptions {
+    PrinterOptions {
         summary_only: cli.summary_only,
-        cwd: cwd.clone(),
+        cwd,
         use_color: !cli.no_color,
         max_entries: cli
             .max_entries
             .unwrap_or(PrinterOptions::default().max_entries),
-    };
-
-    PrettyPrinter::print(&plan, &printer_opts);
+        sort: cli.sort.into(),
+        reverse: cli.reverse,
+        tree: cli.tree,
+        stats: cli.stats,
+    }
+}
 
-    let exec_opts = ExecutorOptions {
+fn executor_options(cli: &Cli, stdin_consumed: bool) -> ExecutorOptions {
+    ExecutorOptions {
         assume_yes: cli.yes,
+        answer: cli.answer.map(|answer| matches!(answer, Answer::Yes)),
+        stdin_consumed,
+        keep_going: cli.keep_going,
+        jobs: cli.jobs,
+        audit: audit_log(cli),
+    }
+}
+
+fn audit_log(cli: &Cli) -> Option<AuditLog> {
+    cli.audit_log.as_ref().map(|path| {
+        let sink = if path.as_os_str() == "syslog" {
+            AuditSink::Syslog
+        } else {
+            AuditSink::File(path.clone())
+        };
+        AuditLog::new(sink, env::args().collect())
+    })
+}
+
+/// Outcome of `--check` for a plan that passed the policy: warnings get
+/// their own exit code so CI can tell them from a clean plan
+fn checked(plan: &Plan) -> Result<(), Error> {
+    if plan.warnings.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::WarningsFound(plan.warnings.len()))
+    }
+}
+
+fn policy(cli: &Cli) -> Policy {
+    Policy {
+        max_actions: cli.max_actions,
+        max_bytes: cli.max_bytes,
+        fail_on: cli.fail_on.into(),
+    }
+}
+
+/// Resolves `--backup` and `-S` like GNU mv: `--backup` without CONTROL
+/// reads `VERSION_CONTROL`, and a suffix alone asks for backups
+fn backup_mode(args: &OverwriteArgs) -> io::Result<BackupMode> {
+    if let Some(Some(mode)) = args.backup {
+        return Ok(mode);
+    }
+    if args.backup.is_none() && args.suffix.is_none() {
+        return Ok(BackupMode::None);
+    }
+
+    match env::var("VERSION_CONTROL") {
+        Ok(control) if !control.is_empty() => BackupMode::from_str(&control, false)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err)),
+        _ => Ok(BackupMode::Existing),
+    }
+}
+
+fn backup_suffix(args: &OverwriteArgs) -> String {
+    args.suffix
+        .clone()
+        .or_else(|| env::var("SIMPLE_BACKUP_SUFFIX").ok())
+        .filter(|suffix| !suffix.is_empty())
+        .unwrap_or_else(|| "~".into())
+}
+
+/// `-n` keeps every existing destination, whatever `--update` says
+fn update_mode(args: &OverwriteArgs) -> UpdateMode {
+    if args.no_clobber {
+        UpdateMode::None
+    } else {
+        args.update.unwrap_or_default()
+    }
+}
+
+fn path_filter(args: FilterArgs) -> io::Result<PathFilter> {
+    let mut exclude = args.exclude;
+    if let Some(path) = &args.exclude_from {
+        exclude.extend(PathFilter::read_patterns(path)?);
+    }
+
+    let gitignore = if args.gitignored_only {
+        GitignoreMode::Only
+    } else if args.respect_gitignore {
+        GitignoreMode::Respect
+    } else {
+        GitignoreMode::Off
     };
 
-    Executor::execute(&plan, &exec_opts)
+    PathFilter::new(&args.include, &exclude)
+        .map(|filter| filter.with_gitignore(gitignore))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
 }
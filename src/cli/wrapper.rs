@@ -0,0 +1,226 @@
+use std::{io, path::PathBuf};
+
+use clap::ValueEnum;
+
+use crate::{
+    cli::args::{Command, FilterArgs, InputArgs, OverwriteArgs},
+    planner::mv::{BackupMode, UpdateMode},
+};
+
+/// A parsed option: its short name, or the long name for long-only options
+#[derive(Debug)]
+struct Opt {
+    name: String,
+    value: Option<String>,
+}
+
+/// Maps a coreutils command line, as typed after `elvis --`, onto a
+/// [`Command`].
+///
+/// Options follow GNU conventions: combined short flags (`-rf`), values
+/// attached or separate (`-tDIR`, `-t DIR`, `--target-directory=DIR`) and
+/// `--` ending option parsing. Anything elvis cannot reproduce faithfully
+/// is rejected instead of being guessed at.
+pub fn parse(words: &[String]) -> io::Result<Command> {
+    let Some((program, args)) = words.split_first() else {
+        return Err(not_previewable("empty command"));
+    };
+
+    // `/bin/rm` and `rm` are the same command
+    let name = PathBuf::from(program)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    match name.as_str() {
+        "rm" => rm(args),
+        "mv" => mv(args),
+        "touch" => touch(args),
+        _ => Err(not_previewable(&format!("`{}` is not supported", name))),
+    }
+}
+
+fn rm(args: &[String]) -> io::Result<Command> {
+    let (opts, operands) = split("rm", args, &[], &[])?;
+
+    let mut recursive = false;
+    let mut force = false;
+    for opt in opts {
+        match opt.name.as_str() {
+            "r" | "R" | "recursive" => recursive = true,
+            "f" | "force" => force = true,
+            // elvis always asks before deleting, and removes empty
+            // directories without -r already
+            "i" | "I" | "interactive" | "d" | "dir" | "v" | "verbose" | "preserve-root" => {}
+            _ => return Err(unsupported("rm", &opt)),
+        }
+    }
+
+    Ok(Command::Rm {
+        targets: operands,
+        recursive,
+        force,
+        filter: FilterArgs::default(),
+        input: InputArgs::default(),
+    })
+}
+
+fn mv(args: &[String]) -> io::Result<Command> {
+    let (opts, operands) = split("mv", args, &["t", "S"], &["target-directory", "suffix"])?;
+
+    let mut force = false;
+    let mut overwrite = OverwriteArgs::default();
+    let mut target_directory = None;
+    let mut no_target_directory = false;
+    for opt in opts {
+        match opt.name.as_str() {
+            // Like GNU mv, the last of -f and -n wins
+            "f" | "force" => {
+                force = true;
+                overwrite.no_clobber = false;
+            }
+            "n" | "no-clobber" => {
+                overwrite.no_clobber = true;
+                force = false;
+            }
+            "u" => overwrite.update = Some(UpdateMode::Older),
+            "update" => {
+                overwrite.update = Some(match &opt.value {
+                    Some(mode) => UpdateMode::from_str(mode, false).map_err(|_| {
+                        not_previewable(&format!("mv: invalid update mode `{}`", mode))
+                    })?,
+                    None => UpdateMode::Older,
+                })
+            }
+            "t" | "target-directory" => target_directory = opt.value.map(PathBuf::from),
+            "T" | "no-target-directory" => no_target_directory = true,
+            "b" => overwrite.backup = Some(None),
+            "backup" => {
+                overwrite.backup = Some(match &opt.value {
+                    Some(control) => Some(BackupMode::from_str(control, false).map_err(|_| {
+                        not_previewable(&format!("mv: invalid backup type `{}`", control))
+                    })?),
+                    None => None,
+                })
+            }
+            "S" | "suffix" => overwrite.suffix = opt.value,
+            "i" | "interactive" | "v" | "verbose" => {}
+            _ => return Err(unsupported("mv", &opt)),
+        }
+    }
+
+    if target_directory.is_none() && operands.len() < 2 {
+        return Err(not_previewable("mv: missing destination operand"));
+    }
+
+    Ok(Command::Mv {
+        paths: operands,
+        target_directory,
+        no_target_directory,
+        force,
+        overwrite,
+        filter: FilterArgs::default(),
+        input: InputArgs::default(),
+    })
+}
+
+fn touch(args: &[String]) -> io::Result<Command> {
+    let (opts, operands) = split("touch", args, &[], &[])?;
+
+    if let Some(opt) = opts.first() {
+        return Err(unsupported("touch", opt));
+    }
+
+    Ok(Command::Touch {
+        targets: operands,
+        input: InputArgs::default(),
+    })
+}
+
+/// Separates options from operands. `short_values` and `long_values` name
+/// the options that take a value.
+fn split(
+    command: &str,
+    args: &[String],
+    short_values: &[&str],
+    long_values: &[&str],
+) -> io::Result<(Vec<Opt>, Vec<PathBuf>)> {
+    let mut opts = Vec::new();
+    let mut operands = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            operands.extend(args.by_ref().map(PathBuf::from));
+            break;
+        }
+
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (long, None),
+            };
+            let value = match value {
+                None if long_values.contains(&name) => Some(next_value(command, name, &mut args)?),
+                value => value,
+            };
+            opts.push(Opt {
+                name: name.to_string(),
+                value,
+            });
+            continue;
+        }
+
+        let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) else {
+            operands.push(PathBuf::from(arg));
+            continue;
+        };
+
+        for (index, flag) in cluster.char_indices() {
+            let name = flag.to_string();
+            if short_values.contains(&name.as_str()) {
+                // The rest of the cluster, or the next word, is the value
+                let rest = &cluster[index + flag.len_utf8()..];
+                let value = if rest.is_empty() {
+                    next_value(command, &name, &mut args)?
+                } else {
+                    rest.to_string()
+                };
+                opts.push(Opt {
+                    name,
+                    value: Some(value),
+                });
+                break;
+            }
+            opts.push(Opt { name, value: None });
+        }
+    }
+
+    Ok((opts, operands))
+}
+
+fn next_value<'a>(
+    command: &str,
+    name: &str,
+    args: &mut impl Iterator<Item = &'a String>,
+) -> io::Result<String> {
+    args.next()
+        .cloned()
+        .ok_or_else(|| not_previewable(&format!("{}: option `{}` requires a value", command, name)))
+}
+
+fn unsupported(command: &str, opt: &Opt) -> io::Error {
+    let flag = if opt.name.len() == 1 {
+        format!("-{}", opt.name)
+    } else {
+        format!("--{}", opt.name)
+    };
+    not_previewable(&format!("{}: option `{}` is not supported", command, flag))
+}
+
+fn not_previewable(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Not previewable: {}", reason),
+    )
+}
@@ -0,0 +1,88 @@
+use std::{fmt, io};
+
+use crate::{executor::precondition::Drift, planner::action::Action};
+
+#[doc = "Why a plan was not, or not completely, applied"]
+#[derive(Debug)]
+pub enum Error {
+    /// The plan has errors, or would fail part way through
+    PlanInvalid(String),
+    /// The user declined the confirmation prompt
+    Cancelled,
+    /// Confirmation is needed but there is no terminal to ask on
+    ConfirmationRequired,
+    /// Applying an action returned an error
+    ActionFailed {
+        action: Box<Action>,
+        source: io::Error,
+    },
+    /// The plan breaks a rule it was checked against
+    PolicyViolation(String),
+    /// The plan has more actions or bytes than allowed
+    LimitExceeded(String),
+    /// A path changed between the preview and applying an action
+    DriftDetected(Drift),
+    /// A checked plan is valid but has this many warnings
+    WarningsFound(usize),
+    /// Every action was applied but the audit record could not be written
+    AuditFailed(io::Error),
+    Io(io::Error),
+}
+
+impl Error {
+    /// Process exit code for this error; 2 is left to argument errors
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Io(_) => 1,
+            Self::ConfirmationRequired => 3,
+            Self::Cancelled => 4,
+            Self::PlanInvalid(_) => 5,
+            Self::ActionFailed { .. } => 6,
+            Self::DriftDetected(_) => 7,
+            Self::PolicyViolation(_) => 8,
+            Self::LimitExceeded(_) => 9,
+            Self::WarningsFound(_) => 10,
+            Self::AuditFailed(_) => 11,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PlanInvalid(reason) => write!(f, "{}", reason),
+            Self::Cancelled => write!(f, "User cancel command"),
+            Self::ConfirmationRequired => write!(
+                f,
+                "Confirmation required but stdin is not a terminal (use -y or --answer)"
+            ),
+            Self::ActionFailed { action, source } => match action.paths().first() {
+                Some(path) => write!(f, "{}: {}", path.display(), source),
+                None => write!(f, "{}", source),
+            },
+            Self::PolicyViolation(rule) => write!(f, "Policy violation: {}", rule),
+            Self::LimitExceeded(limit) => write!(f, "Limit exceeded: {}", limit),
+            Self::DriftDetected(drift) => write!(f, "{}", drift),
+            Self::WarningsFound(count) => write!(f, "Plan has {} warnings", count),
+            Self::AuditFailed(err) => write!(f, "Plan applied, but the audit log failed: {}", err),
+            Self::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ActionFailed { source, .. } | Self::AuditFailed(source) | Self::Io(source) => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
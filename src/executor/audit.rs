@@ -0,0 +1,447 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize, Serializer, ser::Error as _, ser::SerializeSeq};
+
+use crate::{
+    executor::report::{ExecutionReport, FailedAction},
+    planner::{
+        action::Action,
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary, PlanWarning},
+        stream::PlanSpool,
+    },
+};
+
+/// Syslog socket, also served by journald
+#[cfg(unix)]
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// `user.notice`, see RFC 5424
+#[cfg(unix)]
+const SYSLOG_PRIORITY: u8 = 8 + 5;
+
+/// Characters of the command line kept in a syslog message, which has to
+/// fit into one datagram
+const SYSLOG_COMMAND_LINE: usize = 1024;
+
+#[doc = "Where audit records are written"]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditSink {
+    /// Appends JSON lines to a file
+    File(PathBuf),
+    /// Sends one message per record to the local syslog or journald, with
+    /// counts instead of action lists; see [`SyslogSummary`]
+    Syslog,
+}
+
+#[doc = "Appends a record of every executed plan to an audit sink"]
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub sink: AuditSink,
+    /// Arguments elvis was started with, recorded as given
+    pub command_line: Vec<String>,
+}
+
+#[doc = "Who executed which plan where, and what came of it"]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditRecord<A = Vec<Action>, F = Vec<AuditFailure>> {
+    pub time: SystemTime,
+    pub user: String,
+    pub host: String,
+    pub cwd: PathBuf,
+    pub command_line: Vec<String>,
+    pub command: CommandKind,
+    pub summary: PlanSummary,
+    pub warnings: Vec<PlanWarning>,
+    pub applied: A,
+    pub failed: F,
+    pub skipped: A,
+    pub outcome: Outcome,
+}
+
+/// Record of a spooled plan, whose actions are read back from the spool
+/// while the record is written
+pub type SpooledRecord<'a> = AuditRecord<SpooledActions<'a>, SpooledFailures<'a>>;
+
+#[doc = "Actions of a spool at the given plan indices, serialized as a list"]
+#[derive(Debug)]
+pub struct SpooledActions<'a> {
+    spool: &'a PlanSpool,
+    /// Sorted plan indices
+    indices: Vec<usize>,
+}
+
+#[doc = "Failed actions of a spool, serialized like [`AuditFailure`]s"]
+#[derive(Debug)]
+pub struct SpooledFailures<'a> {
+    spool: &'a PlanSpool,
+    /// Sorted by plan index
+    failed: &'a [FailedAction],
+}
+
+#[doc = "Lists of actions in an [`AuditRecord`]"]
+pub trait RecordedActions: Serialize {
+    fn count(&self) -> usize;
+}
+
+#[doc = "An [`AuditRecord`] short enough for one syslog datagram"]
+#[derive(Debug, Serialize)]
+pub struct SyslogSummary<'a> {
+    pub time: SystemTime,
+    pub user: &'a str,
+    pub host: &'a str,
+    pub cwd: &'a Path,
+    /// Shell-quoted and cut after 1024 characters
+    pub command_line: String,
+    pub command: CommandKind,
+    pub summary: &'a PlanSummary,
+    pub warnings: usize,
+    pub applied: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub outcome: Outcome,
+}
+
+#[doc = "Records read back from a file log, numbered by their line"]
+#[derive(Debug, Default)]
+pub struct AuditHistory {
+    /// Records with their 1-based line number, oldest first
+    pub records: Vec<(usize, AuditRecord)>,
+    /// Lines that hold no valid record, with why they could not be read
+    pub invalid: Vec<(usize, String)>,
+}
+
+#[doc = "An action that returned an error, with the error message"]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditFailure {
+    pub action: Action,
+    pub error: String,
+}
+
+#[doc = "Whether every action of an executed plan was applied"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Succeeded,
+    Failed,
+}
+
+impl AuditLog {
+    pub fn new(sink: AuditSink, command_line: Vec<String>) -> Self {
+        Self { sink, command_line }
+    }
+
+    /// Checks that the sink accepts records, so that a misconfigured log
+    /// stops the run before anything is applied. A missing log file is
+    /// created.
+    pub fn check(&self) -> io::Result<()> {
+        match &self.sink {
+            AuditSink::File(path) => Self::open(path).map(drop),
+            AuditSink::Syslog => Self::syslog().map(drop),
+        }
+    }
+
+    pub fn append<A: RecordedActions, F: RecordedActions>(
+        &self,
+        record: &AuditRecord<A, F>,
+    ) -> io::Result<()> {
+        match &self.sink {
+            AuditSink::File(path) => {
+                let file = Self::open(path)?;
+                // Records are written in many pieces; the lock keeps records
+                // of concurrent runs apart
+                file.lock()?;
+                let start = file.metadata()?.len();
+                let written = Self::write_line(&file, record);
+                if written.is_err() {
+                    // Drop the partial line so the log stays readable
+                    let _ = file.set_len(start);
+                }
+                written
+            }
+            AuditSink::Syslog => Self::send_syslog(&serde_json::to_string(&record.summarize())?),
+        }
+    }
+
+    fn write_line(file: &fs::File, record: &impl Serialize) -> io::Result<()> {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, record)?;
+        writer.write_all(b"\n")?;
+        writer.flush()
+    }
+
+    /// Reads back the records of a file log. Blank lines are skipped and
+    /// lines that are not a record are listed apart, so a damaged line
+    /// neither hides the other records nor shifts their numbers.
+    pub fn read(&self) -> io::Result<AuditHistory> {
+        let AuditSink::File(path) = &self.sink else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Syslog records cannot be read back, use journalctl instead",
+            ));
+        };
+
+        let reader = BufReader::new(fs::File::open(path)?);
+        let mut history = AuditHistory::default();
+        for (index, line) in reader.split(b'\n').enumerate() {
+            let line = line?;
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+            match serde_json::from_slice(&line) {
+                Ok(record) => history.records.push((index + 1, record)),
+                Err(err) => history.invalid.push((index + 1, err.to_string())),
+            }
+        }
+        Ok(history)
+    }
+
+    fn open(path: &PathBuf) -> io::Result<fs::File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    #[cfg(unix)]
+    fn syslog() -> io::Result<std::os::unix::net::UnixDatagram> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect(SYSLOG_SOCKET)?;
+        Ok(socket)
+    }
+
+    #[cfg(unix)]
+    fn send_syslog(json: &str) -> io::Result<()> {
+        let message = format!("<{}>elvis[{}]: {}", SYSLOG_PRIORITY, process::id(), json);
+        Self::syslog()?.send(message.as_bytes()).map(drop)
+    }
+
+    #[cfg(not(unix))]
+    fn syslog() -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Syslog is not supported on this platform",
+        ))
+    }
+
+    #[cfg(not(unix))]
+    fn send_syslog(_json: &str) -> io::Result<()> {
+        Self::syslog()
+    }
+}
+
+impl<A, F> AuditRecord<A, F> {
+    fn with_actions(
+        log: &AuditLog,
+        plan: &Plan,
+        report: &ExecutionReport,
+        applied: A,
+        failed: F,
+        skipped: A,
+    ) -> Self {
+        Self {
+            time: SystemTime::now(),
+            user: AuditRecord::user(),
+            host: AuditRecord::host(),
+            cwd: plan.metadata.working_dir.clone(),
+            command_line: log.command_line.clone(),
+            command: plan.metadata.command,
+            summary: plan.summary.clone(),
+            warnings: plan.warnings.clone(),
+            applied,
+            failed,
+            skipped,
+            outcome: if report.is_success() {
+                Outcome::Succeeded
+            } else {
+                Outcome::Failed
+            },
+        }
+    }
+}
+
+impl<A: RecordedActions, F: RecordedActions> AuditRecord<A, F> {
+    /// Counts instead of the warnings and action lists
+    pub fn summarize(&self) -> SyslogSummary<'_> {
+        let mut command_line = shell_words::join(&self.command_line);
+        if let Some((cut, _)) = command_line.char_indices().nth(SYSLOG_COMMAND_LINE) {
+            command_line.truncate(cut);
+            command_line.push_str("...");
+        }
+
+        SyslogSummary {
+            time: self.time,
+            user: &self.user,
+            host: &self.host,
+            cwd: &self.cwd,
+            command_line,
+            command: self.command,
+            summary: &self.summary,
+            warnings: self.warnings.len(),
+            applied: self.applied.count(),
+            failed: self.failed.count(),
+            skipped: self.skipped.count(),
+            outcome: self.outcome,
+        }
+    }
+}
+
+impl<'a> SpooledRecord<'a> {
+    /// Describes an executed spooled plan without reading its actions yet
+    pub fn spooled(log: &AuditLog, spool: &'a PlanSpool, report: &'a ExecutionReport) -> Self {
+        let actions = |indices: Vec<usize>| SpooledActions { spool, indices };
+        Self::with_actions(
+            log,
+            &spool.plan,
+            report,
+            actions(report.succeeded.clone()),
+            SpooledFailures {
+                spool,
+                failed: &report.failed,
+            },
+            actions(report.skipped.iter().map(|s| s.index).collect()),
+        )
+    }
+}
+
+impl AuditRecord {
+    /// Describes an executed plan; `actions` are all actions of the plan,
+    /// indexed like the report
+    pub fn new(log: &AuditLog, plan: &Plan, actions: &[Action], report: &ExecutionReport) -> Self {
+        Self::with_actions(
+            log,
+            plan,
+            report,
+            report
+                .succeeded
+                .iter()
+                .map(|&index| actions[index].clone())
+                .collect(),
+            report
+                .failed
+                .iter()
+                .map(|failed| AuditFailure {
+                    action: actions[failed.index].clone(),
+                    error: failed.error.to_string(),
+                })
+                .collect(),
+            report
+                .skipped
+                .iter()
+                .map(|skipped| actions[skipped.index].clone())
+                .collect(),
+        )
+    }
+
+    /// Whether an attempted action touched `path`, something below it or
+    /// one of its parents; relative paths are taken from the run's cwd
+    pub fn touches(&self, path: &Path) -> bool {
+        let path = self.cwd.join(path);
+        self.applied
+            .iter()
+            .chain(self.failed.iter().map(|failed| &failed.action))
+            .flat_map(Action::paths)
+            .map(|touched| self.cwd.join(touched))
+            .any(|touched| touched.starts_with(&path) || path.starts_with(&touched))
+    }
+
+    /// The executed plan as far as it was recorded: applied actions first,
+    /// then failed and skipped ones
+    pub fn plan(&self) -> Plan {
+        Plan {
+            metadata: PlanMetadata {
+                command: self.command,
+                working_dir: self.cwd.clone(),
+                created_at: self.time,
+            },
+            actions: self
+                .applied
+                .iter()
+                .chain(self.failed.iter().map(|failed| &failed.action))
+                .chain(self.skipped.iter())
+                .cloned()
+                .collect(),
+            warnings: self.warnings.clone(),
+            errors: Vec::new(),
+            summary: self.summary.clone(),
+            patterns: Vec::new(),
+            skipped: Vec::new(),
+            omitted: 0,
+        }
+    }
+
+    fn user() -> String {
+        ["USER", "LOGNAME", "USERNAME"]
+            .into_iter()
+            .find_map(|var| env::var(var).ok())
+            .unwrap_or_else(|| "unknown".into())
+    }
+
+    fn host() -> String {
+        fs::read_to_string("/proc/sys/kernel/hostname")
+            .or_else(|_| fs::read_to_string("/etc/hostname"))
+            .map(|name| name.trim().to_string())
+            .ok()
+            .or_else(|| env::var("HOSTNAME").ok())
+            .or_else(|| env::var("COMPUTERNAME").ok())
+            .unwrap_or_else(|| "unknown".into())
+    }
+}
+
+impl<T: Serialize> RecordedActions for Vec<T> {
+    fn count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl RecordedActions for SpooledActions<'_> {
+    fn count(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+impl RecordedActions for SpooledFailures<'_> {
+    fn count(&self) -> usize {
+        self.failed.len()
+    }
+}
+
+impl Serialize for SpooledActions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.indices.len()))?;
+        for action in pick(self.spool, &self.indices).map_err(S::Error::custom)? {
+            seq.serialize_element(&action.map_err(S::Error::custom)?)?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for SpooledFailures<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let indices: Vec<usize> = self.failed.iter().map(|f| f.index).collect();
+        let mut seq = serializer.serialize_seq(Some(indices.len()))?;
+        let actions = pick(self.spool, &indices).map_err(S::Error::custom)?;
+        for (action, failed) in actions.zip(self.failed) {
+            seq.serialize_element(&AuditFailure {
+                action: action.map_err(S::Error::custom)?,
+                error: failed.error.to_string(),
+            })?;
+        }
+        seq.end()
+    }
+}
+
+/// Reads back the actions of `spool` at the sorted `indices`, one at a time
+fn pick<'a>(
+    spool: &PlanSpool,
+    indices: &'a [usize],
+) -> io::Result<impl Iterator<Item = io::Result<Action>> + use<'a>> {
+    let mut wanted = indices.iter().copied().peekable();
+    Ok(spool
+        .actions()?
+        .enumerate()
+        .filter_map(move |(index, action)| wanted.next_if_eq(&index).map(|_| action)))
+}
@@ -1,54 +1,340 @@
-use std::{fs, io};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{Mutex, mpsc},
+    thread,
+    time::Instant,
+};
 
 use dialoguer::{Confirm, theme::ColorfulTheme};
 use filetime::FileTime;
 
 use crate::{
-    executor::options::ExecutorOptions,
+    error::Error,
+    executor::{
+        audit::AuditRecord,
+        graph::DependencyGraph,
+        options::ExecutorOptions,
+        precondition::Precondition,
+        progress::{Progress, ProgressEvent, ProgressReporter},
+        report::{ExecutionReport, FailedAction, SkippedAction},
+        simulation::Simulation,
+    },
     planner::{
         action::{Action, FsObjectKind},
+        overlay::Entry,
         plan::Plan,
+        stream::PlanSpool,
     },
+    vfs::{real::RealFs, traits::FileSystem},
 };
 
 pub struct Executor;
 
 impl Executor {
-    pub fn execute(plan: &Plan, options: &ExecutorOptions) -> io::Result<()> {
+    /// Confirms and applies the plan.
+    ///
+    /// Errors before anything is applied are returned as `Err`; failures of
+    /// single actions are listed in the report. Each action first checks
+    /// that its paths still hold what the planner recorded for them; see
+    /// [`Precondition::from_action`]. Without
+    /// [`ExecutorOptions::keep_going`] the first failure skips everything
+    /// not started yet, otherwise only the actions touching the same paths.
+    /// With [`ExecutorOptions::jobs`] above one, independent actions run in
+    /// parallel; see [`DependencyGraph`]. With [`ExecutorOptions::audit`],
+    /// a record of the run is appended once it is done; if that fails, the
+    /// report carries the error in [`ExecutionReport::audit_error`].
+    pub fn execute(plan: &Plan, options: &ExecutorOptions) -> Result<ExecutionReport, Error> {
+        Self::execute_with_progress(plan, options, &mut |_: ProgressEvent| {})
+    }
+
+    /// Like [`Executor::execute`], reporting progress after every action
+    pub fn execute_with_progress(
+        plan: &Plan,
+        options: &ExecutorOptions,
+        reporter: &mut dyn ProgressReporter,
+    ) -> Result<ExecutionReport, Error> {
         Self::validate(plan)?;
 
+        // Refuse plans that would stop halfway before anything is changed
+        let simulation = Self::simulate(plan, &RealFs::default());
+        if !options.keep_going
+            && let Some(failure) = simulation.failures.first()
+        {
+            return Err(Error::PlanInvalid(format!(
+                "Simulated execution failed at {}",
+                failure
+            )));
+        }
+
         if !options.assume_yes {
-            Self::confirm()?;
+            Self::confirm(options)?;
+        }
+        // Only after confirming, as opening the log creates it
+        if let Some(audit) = &options.audit {
+            audit.check()?;
         }
 
-        for action in plan.actions.iter() {
-            Self::apply(action)?;
+        let started = Instant::now();
+        let mut progress = Progress {
+            actions_total: plan.actions.len(),
+            bytes_total: plan.actions.iter().map(Action::size).sum(),
+            ..Default::default()
+        };
+        reporter.report(ProgressEvent::Started(progress));
+
+        let mut scheduler = Scheduler::new(plan, options.keep_going);
+        let jobs = options.jobs.max(1);
+
+        let (job_tx, job_rx) = mpsc::channel::<usize>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let job_rx = Mutex::new(job_rx);
+
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                let (job_rx, done_tx) = (&job_rx, done_tx.clone());
+                scope.spawn(move || {
+                    // The lock is released as soon as a job is taken
+                    while let Ok(index) = job_rx.lock().unwrap().recv() {
+                        let action = &plan.actions[index];
+                        let preconditions = Precondition::from_action(action);
+                        let result = Self::attempt(action, &preconditions, index);
+                        if done_tx.send((index, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+
+            let mut running = 0;
+            loop {
+                while running < jobs
+                    && let Some(index) = scheduler.next()
+                {
+                    match scheduler.blocked_by(index) {
+                        Some(cause) => {
+                            scheduler.skip(index, cause);
+                            progress.actions_done += 1;
+                            progress.elapsed = started.elapsed();
+                            reporter.report(ProgressEvent::Advanced(progress));
+                        }
+                        None => {
+                            job_tx.send(index).expect("workers outlive the scheduler");
+                            running += 1;
+                        }
+                    }
+                }
+
+                if running == 0 {
+                    break;
+                }
+
+                let (index, result) = done_rx.recv().expect("a job is running");
+                running -= 1;
+                if result.is_ok() {
+                    progress.bytes_done += plan.actions[index].size();
+                }
+                scheduler.complete(index, result);
+                progress.actions_done += 1;
+                progress.elapsed = started.elapsed();
+                reporter.report(ProgressEvent::Advanced(progress));
+            }
+
+            // Closing the queue lets the idle workers return
+            drop(job_tx);
+        });
+
+        progress.elapsed = started.elapsed();
+        reporter.report(ProgressEvent::Finished(progress));
+
+        let mut report = scheduler.into_report();
+        if let Some(audit) = &options.audit {
+            report.audit_error = audit
+                .append(&AuditRecord::new(audit, plan, &plan.actions, &report))
+                .err();
         }
+        Ok(report)
+    }
 
-        Ok(())
+    /// Checks that the action's paths still hold what the preview was based
+    /// on, then applies it
+    fn attempt(
+        action: &Action,
+        preconditions: &[Precondition],
+        index: usize,
+    ) -> Result<(), FailedAction> {
+        // The tree may have changed while the user was reading the preview
+        if let Some(drift) = preconditions
+            .iter()
+            .find_map(|p| p.check(&RealFs::default()).err())
+        {
+            return Err(FailedAction {
+                index,
+                error: Error::DriftDetected(drift),
+            });
+        }
+
+        Self::apply(action).map_err(|source| FailedAction {
+            index,
+            error: Error::ActionFailed {
+                action: Box::new(action.clone()),
+                source,
+            },
+        })
+    }
+
+    /// Confirms and applies a spooled plan, reading one action at a time.
+    ///
+    /// Actions run sequentially, in plan order, and each is checked right
+    /// before it is applied like in [`Executor::execute`]. Failures are
+    /// handled the same way. The plan is not simulated up front, as its
+    /// actions are not all in memory, so one that fails partway stops with
+    /// the earlier actions applied.
+    pub fn execute_spooled(
+        spool: &PlanSpool,
+        options: &ExecutorOptions,
+        reporter: &mut dyn ProgressReporter,
+    ) -> Result<ExecutionReport, Error> {
+        Self::validate(&spool.plan)?;
+
+        if !options.assume_yes {
+            Self::confirm(options)?;
+        }
+        // Only after confirming, as opening the log creates it
+        if let Some(audit) = &options.audit {
+            audit.check()?;
+        }
+
+        let started = Instant::now();
+        let mut progress = Progress {
+            actions_total: spool.actions_total,
+            bytes_total: spool.bytes_total,
+            ..Default::default()
+        };
+        reporter.report(ProgressEvent::Started(progress));
+
+        let mut report = ExecutionReport::default();
+        // First failure, after which nothing runs without `keep_going`
+        let mut aborted = None;
+        // Paths of failed and skipped actions, which their dependents are
+        // skipped for
+        let mut broken = BrokenPaths::default();
+
+        for (index, action) in spool.actions()?.enumerate() {
+            let action = action?;
+            let cause = if options.keep_going {
+                broken.overlapping(&action)
+            } else {
+                aborted
+            };
+
+            match cause {
+                Some(cause) => {
+                    report.skipped.push(SkippedAction { index, cause });
+                    if options.keep_going {
+                        broken.insert(index, &action);
+                    }
+                }
+                None => {
+                    let preconditions = Precondition::from_action(&action);
+                    match Self::attempt(&action, &preconditions, index) {
+                        Ok(()) => {
+                            report.succeeded.push(index);
+                            progress.bytes_done += action.size();
+                        }
+                        Err(failed) => {
+                            report.failed.push(failed);
+                            aborted.get_or_insert(index);
+                            if options.keep_going {
+                                broken.insert(index, &action);
+                            }
+                        }
+                    }
+                }
+            }
+
+            progress.actions_done += 1;
+            progress.elapsed = started.elapsed();
+            reporter.report(ProgressEvent::Advanced(progress));
+        }
+
+        reporter.report(ProgressEvent::Finished(progress));
+
+        if let Some(audit) = &options.audit {
+            report.audit_error = audit
+                .append(&AuditRecord::spooled(audit, spool, &report))
+                .err();
+        }
+        Ok(report)
+    }
+
+    /// Applies the plan to an in-memory overlay of `fs` instead of disk
+    pub fn simulate(plan: &Plan, fs: &dyn FileSystem) -> Simulation {
+        Simulation::run(plan, fs)
     }
 
-    fn validate(plan: &Plan) -> io::Result<()> {
+    fn validate(plan: &Plan) -> Result<(), Error> {
         if !plan.errors.is_empty() {
-            return Err(io::Error::other("Cannot execute plan with errors"));
+            return Err(Error::PlanInvalid("Cannot execute plan with errors".into()));
         }
         Ok(())
     }
 
-    fn confirm() -> io::Result<()> {
-        if Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Confirm Execution")
-            .default(true)
-            .show_default(false)
-            .interact()
-            .unwrap()
-        {
+    fn confirm(options: &ExecutorOptions) -> Result<(), Error> {
+        let accepted = match options.answer {
+            Some(answer) => answer,
+            None if options.stdin_consumed => Self::ask_tty()?,
+            None if io::stdin().is_terminal() => Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Confirm Execution")
+                .default(true)
+                .show_default(false)
+                .interact()
+                .map_err(io::Error::other)?,
+            None => Self::read_answer(io::stdin().lock())?,
+        };
+
+        if accepted {
             Ok(())
         } else {
-            Err(io::Error::other("User cancel command"))
+            Err(Error::Cancelled)
         }
     }
 
+    /// Asks on the controlling terminal when stdin carried the input paths
+    fn ask_tty() -> Result<bool, Error> {
+        let Ok(mut tty) = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+        else {
+            return Err(Error::ConfirmationRequired);
+        };
+
+        write!(tty, "Confirm Execution [y/N] ")?;
+        tty.flush()?;
+        Self::read_answer(io::BufReader::new(tty))
+    }
+
+    /// Reads a confirmation answer from a non-interactive input.
+    ///
+    /// Only an explicit `y`/`yes` confirms; end of input means nobody
+    /// could answer, which is reported as [`Error::ConfirmationRequired`].
+    fn read_answer(mut input: impl BufRead) -> Result<bool, Error> {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(Error::ConfirmationRequired);
+        }
+
+        Ok(matches!(
+            line.trim().to_ascii_lowercase().as_str(),
+            "y" | "yes"
+        ))
+    }
+
     fn apply(action: &Action) -> io::Result<()> {
         match action {
             Action::Create { path, kind } => match kind {
@@ -69,16 +355,34 @@ impl Executor {
                 from,
                 to,
                 overwrite,
+                ..
             } => {
-                if *overwrite && to.exists() {
-                    if to.is_dir() {
-                        fs::remove_dir_all(to)?;
-                    } else {
-                        fs::remove_file(to)?;
-                    }
+                // Only the kind and size of `to` were checked against the
+                // plan, so a directory is removed only if it is still empty
+                match overwrite {
+                    Some(Entry {
+                        kind: FsObjectKind::Directory,
+                        ..
+                    }) => fs::remove_dir(to)?,
+                    Some(_) => fs::remove_file(to)?,
+                    None => {}
                 }
                 fs::rename(from, to)?
             }
+            Action::Copy { from, to, .. } => {
+                fs::copy(from, to)?;
+            }
+            Action::Link {
+                path,
+                target,
+                symbolic,
+            } => {
+                if *symbolic {
+                    Self::symlink(target, path)?
+                } else {
+                    fs::hard_link(target, path)?
+                }
+            }
             Action::Delete { path, kind, .. } => match kind {
                 FsObjectKind::File | FsObjectKind::Symlink => fs::remove_file(path)?,
                 FsObjectKind::Directory => fs::remove_dir(path)?,
@@ -92,4 +396,150 @@ impl Executor {
 
         Ok(())
     }
+
+    #[cfg(unix)]
+    fn symlink(target: &Path, path: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(target, path)
+    }
+
+    #[cfg(not(unix))]
+    fn symlink(_target: &Path, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Symlink creation not supported on this platform",
+        ))
+    }
+}
+
+/// Paths of broken actions, with the action each was broken by.
+///
+/// A path covers everything below it, so entries below a newly broken
+/// directory are dropped and the set stays as small as the broken subtrees.
+#[derive(Default)]
+struct BrokenPaths {
+    paths: BTreeMap<PathBuf, usize>,
+}
+
+impl BrokenPaths {
+    fn insert(&mut self, index: usize, action: &Action) {
+        for path in action.paths() {
+            if self.covering(path).is_some() {
+                continue;
+            }
+            let below: Vec<PathBuf> = self.below(path).map(|(p, _)| p.clone()).collect();
+            for p in below {
+                self.paths.remove(&p);
+            }
+            self.paths.insert(path.to_path_buf(), index);
+        }
+    }
+
+    /// A broken action touching one of the action's paths, a path inside
+    /// one of them or one of their parents
+    fn overlapping(&self, action: &Action) -> Option<usize> {
+        action.paths().into_iter().find_map(|path| {
+            self.covering(path)
+                .or_else(|| self.below(path).next().map(|(_, &index)| index))
+        })
+    }
+
+    /// The entry for `path` or one of its parents
+    fn covering(&self, path: &Path) -> Option<usize> {
+        path.ancestors().find_map(|p| self.paths.get(p).copied())
+    }
+
+    /// Entries strictly below `path`
+    fn below<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = (&'a PathBuf, &'a usize)> {
+        self.paths
+            .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+            .take_while(move |(p, _)| p.starts_with(path))
+    }
+}
+
+/// Hands out actions whose dependencies are done, lowest index first
+struct Scheduler {
+    graph: DependencyGraph,
+    keep_going: bool,
+    /// Dependencies not completed yet, per action
+    waiting: Vec<usize>,
+    ready: BinaryHeap<Reverse<usize>>,
+    /// Failed or skipped dependency, per action
+    blocked: Vec<Option<usize>>,
+    /// First failure, after which nothing new starts without `keep_going`
+    aborted: Option<usize>,
+    report: ExecutionReport,
+}
+
+impl Scheduler {
+    fn new(plan: &Plan, keep_going: bool) -> Self {
+        let graph = DependencyGraph::from_plan(plan);
+        let waiting: Vec<usize> = graph.dependencies.iter().map(Vec::len).collect();
+        let ready = waiting
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count == 0)
+            .map(|(index, _)| Reverse(index))
+            .collect();
+
+        Self {
+            keep_going,
+            blocked: vec![None; plan.actions.len()],
+            waiting,
+            ready,
+            graph,
+            aborted: None,
+            report: ExecutionReport::default(),
+        }
+    }
+
+    fn next(&mut self) -> Option<usize> {
+        self.ready.pop().map(|Reverse(index)| index)
+    }
+
+    fn blocked_by(&self, index: usize) -> Option<usize> {
+        self.aborted.or(self.blocked[index])
+    }
+
+    fn skip(&mut self, index: usize, cause: usize) {
+        self.report.skipped.push(SkippedAction { index, cause });
+        self.release(index, true);
+    }
+
+    fn complete(&mut self, index: usize, result: Result<(), FailedAction>) {
+        match result {
+            Ok(()) => {
+                self.report.succeeded.push(index);
+                self.release(index, false);
+            }
+            Err(failed) => {
+                self.report.failed.push(failed);
+                if !self.keep_going && self.aborted.is_none() {
+                    self.aborted = Some(index);
+                }
+                self.release(index, true);
+            }
+        }
+    }
+
+    /// Marks `index` done, making its dependents ready once nothing else
+    /// holds them back
+    fn release(&mut self, index: usize, broken: bool) {
+        for &dependent in self.graph.dependents[index].iter() {
+            if broken && self.blocked[dependent].is_none() {
+                self.blocked[dependent] = Some(index);
+            }
+            self.waiting[dependent] -= 1;
+            if self.waiting[dependent] == 0 {
+                self.ready.push(Reverse(dependent));
+            }
+        }
+    }
+
+    /// The report with every list in plan order
+    fn into_report(mut self) -> ExecutionReport {
+        self.report.succeeded.sort_unstable();
+        self.report.failed.sort_by_key(|f| f.index);
+        self.report.skipped.sort_by_key(|s| s.index);
+        self.report
+    }
 }
@@ -0,0 +1,90 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crate::planner::plan::Plan;
+
+#[doc = "Which actions of a plan must run before which"]
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// Earlier actions each action waits for, indexed like the plan
+    pub dependencies: Vec<Vec<usize>>,
+    /// Later actions waiting for each action, indexed like the plan
+    pub dependents: Vec<Vec<usize>>,
+}
+
+impl DependencyGraph {
+    /// Orders every pair of actions that touch the same path, or a path and
+    /// something inside it, the way they appear in the plan. Creates thus
+    /// wait for their parent directory and directory deletes for their
+    /// contents, while actions in unrelated subtrees stay independent.
+    pub fn from_plan(plan: &Plan) -> Self {
+        let count = plan.actions.len();
+        let mut dependencies = vec![Vec::new(); count];
+        let mut dependents = vec![Vec::new(); count];
+
+        // Last action touching exactly each path
+        let mut last: HashMap<PathBuf, usize> = HashMap::new();
+        // Actions touching each path or anything below it that no later
+        // action in that subtree waits for yet
+        let mut frontier: HashMap<PathBuf, BTreeSet<usize>> = HashMap::new();
+
+        for (index, action) in plan.actions.iter().enumerate() {
+            let paths = action.paths();
+
+            let mut deps = BTreeSet::new();
+            for path in paths.iter() {
+                if let Some(below) = frontier.get(*path) {
+                    deps.extend(below.iter().copied());
+                }
+                // Touches of ancestors are ordered among themselves, so
+                // waiting for the latest covers the others
+                if let Some(earlier) = path
+                    .ancestors()
+                    .skip(1)
+                    .filter_map(|ancestor| last.get(ancestor).copied())
+                    .max()
+                {
+                    deps.insert(earlier);
+                }
+            }
+
+            for path in paths {
+                Self::touch(&mut last, &mut frontier, path, index);
+            }
+
+            for &dep in deps.iter() {
+                dependents[dep].push(index);
+            }
+            dependencies[index] = deps.into_iter().collect();
+        }
+
+        Self {
+            dependencies,
+            dependents,
+        }
+    }
+
+    /// Records that `index` touched `path` after everything below it
+    fn touch(
+        last: &mut HashMap<PathBuf, usize>,
+        frontier: &mut HashMap<PathBuf, BTreeSet<usize>>,
+        path: &Path,
+        index: usize,
+    ) {
+        last.insert(path.to_path_buf(), index);
+
+        let covered = frontier
+            .insert(path.to_path_buf(), BTreeSet::from([index]))
+            .unwrap_or_default();
+
+        for ancestor in path.ancestors().skip(1) {
+            let set = frontier.entry(ancestor.to_path_buf()).or_default();
+            for done in covered.iter() {
+                set.remove(done);
+            }
+            set.insert(index);
+        }
+    }
+}
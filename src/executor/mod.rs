@@ -1,2 +1,8 @@
+pub mod audit;
 pub mod execution;
+pub mod graph;
 pub mod options;
+pub mod precondition;
+pub mod progress;
+pub mod report;
+pub mod simulation;
@@ -1,11 +1,30 @@
+use crate::executor::audit::AuditLog;
+
 #[derive(Debug)]
 pub struct ExecutorOptions {
     pub assume_yes: bool,
+    /// Pre-supplied answer to the confirmation prompt
+    pub answer: Option<bool>,
+    /// Stdin was read as input and cannot answer the prompt
+    pub stdin_consumed: bool,
+    /// Keep applying actions that do not depend on a failed one
+    pub keep_going: bool,
+    /// Number of actions applied at the same time
+    pub jobs: usize,
+    /// Where a record of the run is appended once it is done
+    pub audit: Option<AuditLog>,
 }
 
 #[allow(clippy::derivable_impls)]
 impl Default for ExecutorOptions {
     fn default() -> Self {
-        Self { assume_yes: false }
+        Self {
+            assume_yes: false,
+            answer: None,
+            stdin_consumed: false,
+            keep_going: false,
+            jobs: 1,
+            audit: None,
+        }
     }
 }
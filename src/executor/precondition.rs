@@ -0,0 +1,138 @@
+use std::{fmt, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        overlay::Entry,
+    },
+    printer::format::human_size,
+    vfs::traits::FileSystem,
+};
+
+#[doc = "What a path must hold right before an action is applied"]
+#[derive(Debug, Clone, Serialize)]
+pub struct Precondition {
+    pub path: PathBuf,
+    /// `None` when the path must not exist
+    pub expected: Option<Entry>,
+}
+
+#[doc = "A path that no longer holds what the preview was based on"]
+#[derive(Debug)]
+pub struct Drift {
+    pub path: PathBuf,
+    pub expected: Option<Entry>,
+    pub found: Option<Entry>,
+}
+
+impl Precondition {
+    /// Conditions on the paths `action` touches, built from what the
+    /// planner recorded about them right before the action: the moved,
+    /// copied or deleted entry and whatever a move or copy replaces, or
+    /// nothing in the way of a create or link. Directories are compared by
+    /// kind only. Modified entries are not checked.
+    pub fn from_action(action: &Action) -> Vec<Self> {
+        let entry = |kind: FsObjectKind, size: u64| Entry {
+            kind,
+            size: if kind == FsObjectKind::Directory {
+                0
+            } else {
+                size
+            },
+        };
+
+        match action {
+            Action::Create { path, .. } | Action::Link { path, .. } => vec![Self {
+                path: path.clone(),
+                expected: None,
+            }],
+            Action::Delete {
+                path, kind, size, ..
+            } => vec![Self {
+                path: path.clone(),
+                expected: Some(entry(*kind, *size)),
+            }],
+            Action::Move {
+                from,
+                to,
+                kind,
+                overwrite,
+                size,
+                ..
+            } => vec![
+                Self {
+                    path: from.clone(),
+                    expected: Some(entry(*kind, *size)),
+                },
+                Self {
+                    path: to.clone(),
+                    expected: *overwrite,
+                },
+            ],
+            Action::Copy {
+                from,
+                to,
+                overwrite,
+                size,
+                ..
+            } => vec![
+                Self {
+                    path: from.clone(),
+                    expected: Some(entry(FsObjectKind::File, *size)),
+                },
+                Self {
+                    path: to.clone(),
+                    expected: *overwrite,
+                },
+            ],
+            Action::Modify { .. } => Vec::new(),
+        }
+    }
+
+    pub fn check(&self, fs: &dyn FileSystem) -> Result<(), Drift> {
+        let found = fs.symlink_metadata(&self.path).ok().map(Entry::from);
+
+        if found == self.expected {
+            Ok(())
+        } else {
+            Err(Drift {
+                path: self.path.clone(),
+                expected: self.expected,
+                found,
+            })
+        }
+    }
+}
+
+impl Drift {
+    fn describe(entry: Option<Entry>) -> String {
+        match entry {
+            None => "nothing".into(),
+            Some(Entry {
+                kind: FsObjectKind::Directory,
+                ..
+            }) => "a directory".into(),
+            Some(Entry {
+                kind: FsObjectKind::Symlink,
+                ..
+            }) => "a symlink".into(),
+            Some(Entry { size, .. }) => format!("a file of {}", human_size(size)),
+        }
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Changed since preview: {} was expected to be {}, found {}",
+            self.path.display(),
+            Self::describe(self.expected),
+            Self::describe(self.found)
+        )
+    }
+}
+
+impl std::error::Error for Drift {}
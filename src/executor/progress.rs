@@ -0,0 +1,74 @@
+use std::{sync::mpsc::Sender, time::Duration};
+
+#[doc = "Counters of an execution in progress"]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Progress {
+    /// Actions applied, failed or skipped so far
+    pub actions_done: usize,
+    pub actions_total: usize,
+    /// Bytes of existing data touched by the applied actions
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub elapsed: Duration,
+}
+
+#[doc = "Event emitted by the executor while applying a plan"]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressEvent {
+    /// Confirmation was given and the first action is about to be applied
+    Started(Progress),
+    /// One more action was applied, failed or skipped
+    Advanced(Progress),
+    Finished(Progress),
+}
+
+#[doc = "Receives progress events, e.g. to drive a progress bar"]
+pub trait ProgressReporter {
+    fn report(&mut self, event: ProgressEvent);
+}
+
+impl<F: FnMut(ProgressEvent)> ProgressReporter for F {
+    fn report(&mut self, event: ProgressEvent) {
+        self(event)
+    }
+}
+
+impl ProgressReporter for Sender<ProgressEvent> {
+    fn report(&mut self, event: ProgressEvent) {
+        // A receiver that went away just stops listening
+        let _ = self.send(event);
+    }
+}
+
+impl Progress {
+    /// Bytes per second so far
+    pub fn bytes_per_sec(&self) -> f64 {
+        Self::per_sec(self.bytes_done as f64, self.elapsed)
+    }
+
+    /// Actions per second so far
+    pub fn actions_per_sec(&self) -> f64 {
+        Self::per_sec(self.actions_done as f64, self.elapsed)
+    }
+
+    /// Estimated time left, from bytes when the plan touches data and from
+    /// actions otherwise
+    pub fn eta(&self) -> Option<Duration> {
+        let (done, total) = if self.bytes_total > 0 {
+            (self.bytes_done as f64, self.bytes_total as f64)
+        } else {
+            (self.actions_done as f64, self.actions_total as f64)
+        };
+
+        if done <= 0.0 {
+            return None;
+        }
+        let left = (total - done).max(0.0);
+        Some(self.elapsed.mul_f64(left / done))
+    }
+
+    fn per_sec(done: f64, elapsed: Duration) -> f64 {
+        let secs = elapsed.as_secs_f64();
+        if secs > 0.0 { done / secs } else { 0.0 }
+    }
+}
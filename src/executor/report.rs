@@ -0,0 +1,60 @@
+use std::io;
+
+use serde::{Serialize, Serializer};
+
+use crate::error::Error;
+
+#[doc = "What happened to each action of an executed plan"]
+#[derive(Debug, Default, Serialize)]
+pub struct ExecutionReport {
+    /// Positions of the actions that were applied, starting at zero
+    pub succeeded: Vec<usize>,
+    pub failed: Vec<FailedAction>,
+    pub skipped: Vec<SkippedAction>,
+    /// Why the audit record of the run could not be written
+    #[serde(skip)]
+    pub audit_error: Option<io::Error>,
+}
+
+#[doc = "An action that was attempted and returned an error"]
+#[derive(Debug, Serialize)]
+pub struct FailedAction {
+    pub index: usize,
+    /// [`Error::ActionFailed`], or [`Error::DriftDetected`] when the action
+    /// was not applied because its paths changed since the preview
+    #[serde(serialize_with = "serialize_display")]
+    pub error: Error,
+}
+
+#[doc = "An action that was not attempted"]
+#[derive(Debug, Serialize)]
+pub struct SkippedAction {
+    pub index: usize,
+    /// The failed or skipped action this one depends on
+    pub cause: usize,
+}
+
+impl ExecutionReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+
+    /// The first failure, which stops execution unless keeping going, or
+    /// else the audit log failure
+    pub fn into_error(self) -> Option<Error> {
+        match self.failed.into_iter().next() {
+            Some(failed) => Some(failed.error),
+            None => self.audit_error.map(Error::AuditFailed),
+        }
+    }
+}
+
+impl FailedAction {
+    pub fn drifted(&self) -> bool {
+        matches!(self.error, Error::DriftDetected(_))
+    }
+}
+
+fn serialize_display<S: Serializer>(error: &Error, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(error)
+}
@@ -0,0 +1,250 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        overlay::{Entry, Overlay},
+        plan::Plan,
+    },
+    vfs::traits::FileSystem,
+};
+
+#[doc = "Outcome of applying a plan to an in-memory overlay of a tree"]
+#[derive(Debug, Default, Serialize)]
+pub struct Simulation {
+    pub failures: Vec<SimulatedFailure>,
+    /// Paths that differ from the tree once every action has been applied,
+    /// sorted by path
+    pub state: Vec<PredictedEntry>,
+}
+
+#[doc = "An action that would fail if the plan were executed"]
+#[derive(Debug, Serialize)]
+pub struct SimulatedFailure {
+    /// Position of the action in the plan, starting at zero
+    pub index: usize,
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[doc = "What a path is predicted to hold after execution"]
+#[derive(Debug, Serialize)]
+pub struct PredictedEntry {
+    pub path: PathBuf,
+    /// `None` when the path will no longer exist
+    pub kind: Option<FsObjectKind>,
+    pub size: u64,
+}
+
+impl fmt::Display for SimulatedFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "action {}: {}: {}",
+            self.index + 1,
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+/// Why an action cannot be applied, and to which path
+type Failure = (PathBuf, &'static str);
+
+impl Simulation {
+    /// Applies every action of `plan` to an overlay of `fs`.
+    ///
+    /// A failing action is recorded and left out, so the actions after it
+    /// are checked against the tree as it would be if it was skipped.
+    pub fn run(plan: &Plan, fs: &dyn FileSystem) -> Self {
+        let mut overlay = Overlay::new(fs);
+        let mut failures = Vec::new();
+
+        for (index, action) in plan.actions.iter().enumerate() {
+            if let Err((path, message)) = Self::apply(&mut overlay, action) {
+                failures.push(SimulatedFailure {
+                    index,
+                    path,
+                    message: message.into(),
+                });
+            }
+        }
+
+        let state = overlay
+            .changes()
+            .into_iter()
+            .map(|(path, entry)| PredictedEntry {
+                path,
+                kind: entry.map(|e| e.kind),
+                size: entry.map_or(0, |e| e.size),
+            })
+            .collect();
+
+        Self { failures, state }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn apply(overlay: &mut Overlay, action: &Action) -> Result<(), Failure> {
+        match action {
+            Action::Create { path, kind } => match kind {
+                FsObjectKind::File => {
+                    if overlay.is_dir(path) {
+                        return Err((path.clone(), "Is a directory"));
+                    }
+                    Self::require_parent(overlay, path)?;
+                    overlay.insert(path.clone(), Self::entry(FsObjectKind::File, 0));
+                }
+                FsObjectKind::Directory => {
+                    if overlay.exists(path) {
+                        return Err((path.clone(), "File exists"));
+                    }
+                    Self::require_parent(overlay, path)?;
+                    overlay.insert(path.clone(), Self::entry(FsObjectKind::Directory, 0));
+                }
+                FsObjectKind::Symlink => {
+                    return Err((path.clone(), "Symlink creation not supported yet"));
+                }
+            },
+            Action::Move {
+                from,
+                to,
+                overwrite,
+                ..
+            } => {
+                let entry = Self::require(overlay, from)?;
+                Self::require_parent(overlay, to)?;
+                if to.starts_with(from) {
+                    return Err((to.clone(), "Cannot move a directory into itself"));
+                }
+
+                if let Some(existing) = overlay.get(to) {
+                    // Like the executor, only what the plan recorded is
+                    // removed, and a directory only when it is empty
+                    if let Some(recorded) = overwrite {
+                        let recorded_dir = recorded.kind == FsObjectKind::Directory;
+                        let existing_dir = existing.kind == FsObjectKind::Directory;
+                        if recorded_dir && !existing_dir {
+                            return Err((to.clone(), "Not a directory"));
+                        }
+                        if !recorded_dir && existing_dir {
+                            return Err((to.clone(), "Is a directory"));
+                        }
+                        if existing_dir && !overlay.children(to).is_empty() {
+                            return Err((to.clone(), "Directory not empty"));
+                        }
+                        Self::remove_tree(overlay, to);
+                    } else if entry.kind == FsObjectKind::Directory {
+                        if existing.kind != FsObjectKind::Directory {
+                            return Err((to.clone(), "Not a directory"));
+                        }
+                        if !overlay.children(to).is_empty() {
+                            return Err((to.clone(), "Directory not empty"));
+                        }
+                    } else if existing.kind == FsObjectKind::Directory {
+                        return Err((to.clone(), "Is a directory"));
+                    }
+                }
+
+                for path in overlay.descendants(from) {
+                    if let Some(moved) = overlay.get(&path) {
+                        overlay.insert(to.join(path.strip_prefix(from).unwrap()), moved);
+                        overlay.remove(path);
+                    }
+                }
+                overlay.remove(from.clone());
+                overlay.insert(to.clone(), entry);
+            }
+            Action::Copy { from, to, .. } => {
+                let entry = Self::require(overlay, from)?;
+                if entry.kind == FsObjectKind::Directory {
+                    return Err((from.clone(), "Is a directory"));
+                }
+                if overlay.is_dir(to) {
+                    return Err((to.clone(), "Is a directory"));
+                }
+                Self::require_parent(overlay, to)?;
+                overlay.insert(to.clone(), Self::entry(FsObjectKind::File, entry.size));
+            }
+            Action::Link {
+                path,
+                target,
+                symbolic,
+            } => {
+                if overlay.exists(path) {
+                    return Err((path.clone(), "File exists"));
+                }
+                Self::require_parent(overlay, path)?;
+
+                let entry = if *symbolic {
+                    Self::entry(FsObjectKind::Symlink, 0)
+                } else {
+                    let entry = Self::require(overlay, target)?;
+                    if entry.kind == FsObjectKind::Directory {
+                        return Err((target.clone(), "Hard link not allowed for directory"));
+                    }
+                    entry
+                };
+                overlay.insert(path.clone(), entry);
+            }
+            Action::Delete { path, kind, .. } => {
+                let entry = Self::require(overlay, path)?;
+                match kind {
+                    FsObjectKind::File | FsObjectKind::Symlink => {
+                        if entry.kind == FsObjectKind::Directory {
+                            return Err((path.clone(), "Is a directory"));
+                        }
+                    }
+                    FsObjectKind::Directory => {
+                        if entry.kind != FsObjectKind::Directory {
+                            return Err((path.clone(), "Not a directory"));
+                        }
+                        if !overlay.children(path).is_empty() {
+                            return Err((path.clone(), "Directory not empty"));
+                        }
+                    }
+                }
+                overlay.remove(path.clone());
+            }
+            Action::Modify { path, .. } => {
+                Self::require(overlay, path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn require(overlay: &Overlay, path: &Path) -> Result<Entry, Failure> {
+        overlay
+            .get(path)
+            .ok_or_else(|| (path.to_path_buf(), "No such file or directory"))
+    }
+
+    fn require_parent(overlay: &Overlay, path: &Path) -> Result<(), Failure> {
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() && !overlay.is_dir(parent) => {
+                Err((path.to_path_buf(), "No such file or directory"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes `path` and everything below it, like `remove_dir_all`
+    fn remove_tree(overlay: &mut Overlay, path: &Path) {
+        for child in overlay.descendants(path) {
+            overlay.remove(child);
+        }
+        overlay.remove(path.to_path_buf());
+    }
+
+    fn entry(kind: FsObjectKind, size: u64) -> Entry {
+        Entry { kind, size }
+    }
+}
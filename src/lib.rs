@@ -1,4 +1,6 @@
 pub mod cli;
+pub mod error;
 pub mod executor;
 pub mod planner;
 pub mod printer;
+pub mod vfs;
@@ -6,6 +6,6 @@ fn main() {
 
     if let Err(err) = cli::run(cli) {
         eprintln!("Error: {}", err);
-        std::process::exit(1);
+        std::process::exit(err.exit_code());
     }
 }
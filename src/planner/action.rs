@@ -1,7 +1,14 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::planner::overlay::Entry;
 
 #[doc = "Plan Action"]
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Action {
     Create {
         path: PathBuf,
@@ -10,22 +17,125 @@ pub enum Action {
     Move {
         from: PathBuf,
         to: PathBuf,
-        overwrite: bool,
+        /// Kind of the moved entry
+        #[serde(default)]
+        kind: FsObjectKind,
+        /// What `to` held when planned, `None` when it did not exist
+        #[serde(default, deserialize_with = "overwritten")]
+        overwrite: Option<Entry>,
+        /// Size of the moved entry in bytes
+        size: u64,
+        /// Modification time of the moved entry when planned
+        modified: Option<SystemTime>,
+        /// Whether this renames an overwritten destination to its backup
+        #[serde(default)]
+        backup: bool,
+    },
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+        /// What `to` held when planned, `None` when it did not exist
+        #[serde(default, deserialize_with = "overwritten")]
+        overwrite: Option<Entry>,
+        /// Size of the copied file in bytes
+        size: u64,
+        /// Modification time of the copied file when planned
+        modified: Option<SystemTime>,
+    },
+    Link {
+        path: PathBuf,
+        target: PathBuf,
+        symbolic: bool,
     },
     Modify {
         path: PathBuf,
+        #[serde(default)]
+        kind: FsObjectKind,
         description: String,
+        /// Modification time of the entry when planned
+        modified: Option<SystemTime>,
     },
     Delete {
         path: PathBuf,
         kind: FsObjectKind,
         recursive: bool,
+        /// Size of the deleted entry in bytes
+        size: u64,
+        /// Modification time of the deleted entry when planned
+        modified: Option<SystemTime>,
     },
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Action {
+    /// Bytes of existing data the action touches
+    pub fn size(&self) -> u64 {
+        match self {
+            Action::Move { size, .. } | Action::Copy { size, .. } | Action::Delete { size, .. } => {
+                *size
+            }
+            Action::Create { .. } | Action::Link { .. } | Action::Modify { .. } => 0,
+        }
+    }
+
+    /// Modification time of the existing entry the action touches, as
+    /// recorded when it was planned
+    pub fn modified(&self) -> Option<SystemTime> {
+        match self {
+            Action::Move { modified, .. }
+            | Action::Copy { modified, .. }
+            | Action::Modify { modified, .. }
+            | Action::Delete { modified, .. } => *modified,
+            Action::Create { .. } | Action::Link { .. } => None,
+        }
+    }
+
+    /// Every path the action reads or changes
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            Action::Create { path, .. }
+            | Action::Modify { path, .. }
+            | Action::Delete { path, .. } => vec![path],
+            Action::Link { path, target, .. } => vec![path, target],
+            Action::Move { from, to, .. } | Action::Copy { from, to, .. } => vec![from, to],
+        }
+    }
+
+    /// Whether both actions touch the same path, or one touches a path
+    /// inside a directory the other touches
+    pub fn overlaps(&self, other: &Action) -> bool {
+        self.paths().iter().any(|a| {
+            other
+                .paths()
+                .iter()
+                .any(|b| a.starts_with(b) || b.starts_with(a))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FsObjectKind {
+    #[default]
     File,
     Directory,
     Symlink,
 }
+
+/// Reads `overwrite`, which older audit records hold as a flag
+fn overwritten<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Entry>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Overwrite {
+        Flag(bool),
+        Entry(Option<Entry>),
+    }
+
+    Ok(match Overwrite::deserialize(deserializer)? {
+        Overwrite::Flag(false) => None,
+        // Only known to have existed
+        Overwrite::Flag(true) => Some(Entry {
+            kind: FsObjectKind::File,
+            size: 0,
+        }),
+        Overwrite::Entry(entry) => entry,
+    })
+}
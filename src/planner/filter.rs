@@ -0,0 +1,115 @@
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::{
+    planner::gitignore::{GitignoreMode, IgnoreMatcher},
+    vfs::traits::FileSystem,
+};
+
+#[doc = "Include and exclude rules applied while walking directories"]
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    gitignore: GitignoreMode,
+    ignore: IgnoreMatcher,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        Ok(Self {
+            include: Self::build(include)?,
+            exclude: Self::build(exclude)?,
+            ..Self::default()
+        })
+    }
+
+    pub fn with_gitignore(mut self, mode: GitignoreMode) -> Self {
+        self.gitignore = mode;
+        self
+    }
+
+    /// Reads exclude patterns from a file, one per line.
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn read_patterns(path: &Path) -> io::Result<Vec<String>> {
+        Ok(fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect())
+    }
+
+    /// Whether an entry found while walking `root` must be left in place.
+    ///
+    /// An entry is kept when it or one of its parents matches an exclude
+    /// pattern, when ignore rules say so, or when include patterns are set
+    /// and a file matches none of them. Directories are never kept by
+    /// include patterns alone; they are removed once everything inside them
+    /// is. Ignore files are read through `fs`.
+    pub fn keeps(&self, fs: &dyn FileSystem, root: &Path, path: &Path, is_dir: bool) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+
+        if let Some(exclude) = &self.exclude
+            && relative
+                .ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| Self::matches(exclude, p))
+        {
+            return true;
+        }
+
+        // The repository itself is never part of an ignore-driven cleanup
+        if self.gitignore != GitignoreMode::Off
+            && relative.components().any(|c| c.as_os_str() == ".git")
+        {
+            return true;
+        }
+
+        let kept_by_ignore = match self.gitignore {
+            GitignoreMode::Off => false,
+            GitignoreMode::Respect => self.ignore.is_ignored(fs, path, is_dir),
+            GitignoreMode::Only => !self.ignore.is_ignored(fs, path, is_dir),
+        };
+        if kept_by_ignore {
+            return true;
+        }
+
+        match &self.include {
+            Some(include) if !is_dir => !Self::matches(include, relative),
+            _ => false,
+        }
+    }
+
+    /// Records every parent of a kept entry, up to and including `root`, so
+    /// that those directories are not planned for deletion.
+    pub fn mark_parents(kept: &mut HashSet<PathBuf>, root: &Path, path: &Path) {
+        for parent in path.ancestors().skip(1) {
+            if !parent.starts_with(root) || !kept.insert(parent.to_path_buf()) {
+                break;
+            }
+        }
+    }
+
+    fn matches(set: &GlobSet, path: &Path) -> bool {
+        set.is_match(path) || path.file_name().is_some_and(|name| set.is_match(name))
+    }
+
+    fn build(patterns: &[String]) -> Result<Option<GlobSet>, globset::Error> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+        builder.build().map(Some)
+    }
+}
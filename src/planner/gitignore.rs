@@ -0,0 +1,95 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::vfs::traits::FileSystem;
+
+/// Ignore files read from every directory, in increasing precedence
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+#[doc = "How `.gitignore`/`.ignore` rules select entries while walking"]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GitignoreMode {
+    /// Ignore rules are not read
+    #[default]
+    Off,
+    /// Ignored entries are kept
+    Respect,
+    /// Only ignored entries are affected
+    Only,
+}
+
+#[doc = "Matches paths against the ignore files of their parent directories"]
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreMatcher {
+    /// Parsed ignore rules per directory, `None` when it has none
+    cache: RefCell<HashMap<PathBuf, Option<Gitignore>>>,
+}
+
+impl IgnoreMatcher {
+    /// Whether `path` is ignored by the closest ignore file that has an
+    /// opinion on it. Lookup stops at the repository root. Ignore files are
+    /// read through `fs`.
+    pub fn is_ignored(&self, fs: &dyn FileSystem, path: &Path, is_dir: bool) -> bool {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let path = path.as_path();
+
+        for dir in path.ancestors().skip(1) {
+            if dir.as_os_str().is_empty() {
+                break;
+            }
+
+            let matched = self.with_rules(fs, dir, |rules| {
+                let m = rules.matched_path_or_any_parents(path, is_dir);
+                (!m.is_none()).then(|| m.is_ignore())
+            });
+            if let Some(ignored) = matched {
+                return ignored;
+            }
+
+            if fs.exists(&dir.join(".git")) {
+                break;
+            }
+        }
+
+        false
+    }
+
+    fn with_rules(
+        &self,
+        fs: &dyn FileSystem,
+        dir: &Path,
+        f: impl FnOnce(&Gitignore) -> Option<bool>,
+    ) -> Option<bool> {
+        let mut cache = self.cache.borrow_mut();
+        let rules = cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| Self::load(fs, dir));
+        rules.as_ref().and_then(f)
+    }
+
+    fn load(fs: &dyn FileSystem, dir: &Path) -> Option<Gitignore> {
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        for name in IGNORE_FILES {
+            let file = dir.join(name);
+            let Ok(text) = fs.read_to_string(&file) else {
+                continue;
+            };
+            // Malformed lines are skipped like git does
+            for line in text.lines() {
+                let _ = builder.add_line(Some(file.clone()), line);
+            }
+            found = true;
+        }
+
+        if !found {
+            return None;
+        }
+        builder.build().ok().filter(|rules| !rules.is_empty())
+    }
+}
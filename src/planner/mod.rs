@@ -1,6 +1,13 @@
 pub mod action;
+pub mod filter;
+pub mod gitignore;
 pub mod mv;
+pub mod overlay;
 pub mod plan;
+pub mod policy;
 pub mod rm;
+pub mod script;
+pub mod stream;
 pub mod touch;
 pub mod traits;
+pub mod vcs;
@@ -1,12 +1,24 @@
-use std::{path::PathBuf, time::SystemTime};
-use walkdir::WalkDir;
-
-use crate::planner::{
-    action::{Action, FsObjectKind},
-    plan::{
-        CommandKind, ErrorKind, Plan, PlanError, PlanMetadata, PlanSummary, PlanWarning,
-        WarningKind,
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        filter::PathFilter,
+        overlay::Entry,
+        plan::{
+            CommandKind, ErrorKind, Plan, PlanError, PlanMetadata, PlanSummary, PlanWarning,
+            SkipReason, SkippedEntry, WarningKind,
+        },
+        vcs::VcsCheck,
     },
+    vfs::traits::{FileSystem, Metadata},
 };
 
 #[doc = "Planner for `mv`"]
@@ -15,6 +27,58 @@ pub struct MvPlanner {
     pub target: PathBuf,
     pub force: bool,
     pub cwd: PathBuf,
+    pub filter: PathFilter,
+    /// Treat the target as the destination path itself, never as a
+    /// directory to move into (`mv -T`)
+    pub no_target_directory: bool,
+    /// How destinations about to be overwritten are kept (`mv --backup`)
+    pub backup: BackupMode,
+    /// Appended to simple backups, `~` by default
+    pub suffix: String,
+    /// Which existing destinations are replaced (`mv -u`, `mv -n`)
+    pub update: UpdateMode,
+}
+
+#[doc = "Which existing destinations mv replaces"]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum UpdateMode {
+    /// Replace every destination
+    #[default]
+    All,
+    /// Replace no destination, like `--no-clobber`
+    None,
+    /// Replace destinations older than their source
+    Older,
+}
+
+#[doc = "GNU version control for backups of overwritten destinations"]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BackupMode {
+    /// Overwrite without a backup
+    #[default]
+    #[value(alias = "off")]
+    None,
+    /// Rename the destination to DEST plus the suffix
+    #[value(alias = "never")]
+    Simple,
+    /// Rename the destination to DEST.~N~, after the highest existing N
+    #[value(alias = "t")]
+    Numbered,
+    /// Numbered if numbered backups of DEST exist already, simple otherwise
+    #[value(alias = "nil")]
+    Existing,
+}
+
+/// Actions, findings and backup names of the plan being built
+#[derive(Default)]
+struct Pending {
+    actions: Vec<Action>,
+    warnings: Vec<PlanWarning>,
+    errors: Vec<PlanError>,
+    summary: PlanSummary,
+    skipped: Vec<SkippedEntry>,
+    /// Backup paths taken by earlier actions of this plan
+    backups: HashSet<PathBuf>,
 }
 
 impl MvPlanner {
@@ -24,29 +88,241 @@ impl MvPlanner {
             target,
             force,
             cwd,
+            filter: PathFilter::default(),
+            no_target_directory: false,
+            backup: BackupMode::None,
+            suffix: "~".into(),
+            update: UpdateMode::All,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: PathFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_no_target_directory(mut self, no_target_directory: bool) -> Self {
+        self.no_target_directory = no_target_directory;
+        self
+    }
+
+    pub fn with_backup(mut self, backup: BackupMode, suffix: String) -> Self {
+        self.backup = backup;
+        self.suffix = suffix;
+        self
+    }
+
+    pub fn with_update(mut self, update: UpdateMode) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Plans moving one file, unless its destination is to be kept. An
+    /// existing destination is first renamed to its backup, or overwritten
+    /// with a warning when there is no backup, also with `force`; a
+    /// directory in the way is an error, like in GNU mv. Returns whether the
+    /// file is moved.
+    fn move_file(
+        &self,
+        fs: &dyn FileSystem,
+        pending: &mut Pending,
+        from: PathBuf,
+        to: PathBuf,
+        metadata: Metadata,
+    ) -> bool {
+        let existing = fs.symlink_metadata(&to).ok();
+        if existing.is_some_and(|m| m.is_dir()) && !metadata.is_dir() {
+            pending.errors.push(PlanError {
+                kind: ErrorKind::InvalidPath,
+                path: Some(to),
+                message: "Cannot overwrite directory with non-directory".into(),
+            });
+            return false;
+        }
+
+        if existing.is_some()
+            && let Some(reason) = self.skip_reason(fs, &from, &to)
+        {
+            pending.skipped.push(SkippedEntry {
+                path: from,
+                destination: to,
+                reason,
+            });
+            pending.summary.files_skipped += 1;
+            return false;
+        }
+
+        // What `to` holds and loses once the file is moved
+        let mut overwritten = existing;
+        if let Some(dest) = existing
+            && let Some(backup) = self.backup_path(fs, &to, &pending.backups)
+        {
+            let replaced = fs.symlink_metadata(&backup).ok();
+            match replaced {
+                Some(old) if old.is_dir() => {
+                    pending.errors.push(PlanError {
+                        kind: ErrorKind::InvalidPath,
+                        path: Some(backup),
+                        message: "Cannot overwrite directory with non-directory".into(),
+                    });
+                    return false;
+                }
+                Some(old) => {
+                    pending.warnings.push(PlanWarning {
+                        kind: WarningKind::Overwrite,
+                        paths: vec![backup.clone()],
+                        message: "Previous backup will be overwritten".into(),
+                    });
+                    pending.summary.bytes_overwritten += old.len;
+                }
+                None => {}
+            }
+
+            pending.backups.insert(backup.clone());
+            pending.actions.push(Action::Move {
+                from: to.clone(),
+                to: backup,
+                kind: dest.kind,
+                overwrite: replaced.map(Entry::from),
+                size: dest.len,
+                modified: dest.modified,
+                backup: true,
+            });
+            pending.summary.files_backed_up += 1;
+            overwritten = None;
+        } else if existing.is_some() {
+            pending.warnings.push(PlanWarning {
+                kind: WarningKind::Overwrite,
+                paths: vec![to.clone()],
+                message: "Dest will be overwrite".into(),
+            });
+        }
+        // Directories in the way are errors above, so this is never a
+        // directory counted as empty
+        if let Some(dest) = overwritten {
+            pending.summary.bytes_overwritten += dest.len;
         }
+
+        pending.actions.push(Action::Move {
+            from,
+            to,
+            kind: metadata.kind,
+            overwrite: overwritten.map(Entry::from),
+            size: metadata.len,
+            modified: metadata.modified,
+            backup: false,
+        });
+        pending.summary.files_moved += 1;
+        pending.summary.bytes_moved += metadata.len;
+        true
+    }
+
+    /// Why the existing destination `to` is kept instead of replaced
+    fn skip_reason(&self, fs: &dyn FileSystem, from: &Path, to: &Path) -> Option<SkipReason> {
+        match self.update {
+            UpdateMode::All => None,
+            UpdateMode::None => Some(SkipReason::DestinationExists),
+            UpdateMode::Older => {
+                let modified = |path| fs.symlink_metadata(path).ok()?.modified;
+                // Replace when either time is unknown, like GNU mv
+                match (modified(from), modified(to)) {
+                    (Some(source), Some(dest)) if dest >= source => {
+                        Some(SkipReason::DestinationNotOlder)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Plans creating a destination directory unless it exists already
+    fn create_dir(fs: &dyn FileSystem, pending: &mut Pending, dest: PathBuf) {
+        if fs.symlink_metadata(&dest).is_ok_and(|m| !m.is_dir()) {
+            pending.errors.push(PlanError {
+                kind: ErrorKind::InvalidPath,
+                path: Some(dest),
+                message: "Cannot overwrite non-directory with directory".into(),
+            });
+        } else if !fs.exists(&dest) {
+            pending.actions.push(Action::Create {
+                path: dest,
+                kind: FsObjectKind::Directory,
+            });
+            pending.summary.dirs_created += 1;
+        }
+    }
+
+    /// Where `dest` is renamed to before being overwritten, if anywhere
+    fn backup_path(
+        &self,
+        fs: &dyn FileSystem,
+        dest: &Path,
+        taken: &HashSet<PathBuf>,
+    ) -> Option<PathBuf> {
+        let numbered = match self.backup {
+            BackupMode::None => return None,
+            BackupMode::Simple => false,
+            BackupMode::Numbered => true,
+            BackupMode::Existing => Self::last_backup(fs, dest, taken) > 0,
+        };
+
+        let mut name = dest.as_os_str().to_owned();
+        if numbered {
+            name.push(format!(".~{}~", Self::last_backup(fs, dest, taken) + 1));
+        } else {
+            name.push(&self.suffix);
+        }
+        Some(PathBuf::from(name))
+    }
+
+    /// Highest N of the existing or planned `dest.~N~` backups, 0 if none
+    fn last_backup(fs: &dyn FileSystem, dest: &Path, taken: &HashSet<PathBuf>) -> u64 {
+        let Some(file_name) = dest.file_name() else {
+            return 0;
+        };
+        let mut prefix = OsString::from(file_name);
+        prefix.push(".~");
+        let prefix = prefix.to_string_lossy().into_owned();
+
+        let parent = match dest.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs.read_dir(parent)
+            .unwrap_or_default()
+            .iter()
+            .chain(taken.iter().filter(|path| path.parent() == dest.parent()))
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy();
+                name.strip_prefix(&prefix)?.strip_suffix('~')?.parse().ok()
+            })
+            .max()
+            .unwrap_or(0)
     }
 }
 
 impl super::traits::Planner for MvPlanner {
-    fn plan(&self) -> Plan {
-        let mut actions = Vec::new();
-        let mut warnings = Vec::new();
-        let mut errors = Vec::new();
-        let mut summary = PlanSummary::default();
+    fn plan(&self, fs: &dyn FileSystem) -> Plan {
+        let mut pending = Pending::default();
 
-        let target_exist = self.target.exists();
-        let target_is_dir = target_exist && self.target.is_dir();
+        let target_exist = fs.exists(&self.target);
+        let target_is_dir = target_exist && fs.is_dir(&self.target) && !self.no_target_directory;
 
-        if self.sources.len() > 1 {
+        if self.no_target_directory && self.sources.len() > 1 {
+            pending.errors.push(PlanError {
+                kind: ErrorKind::InvalidPath,
+                path: Some(self.target.clone()),
+                message: "Only one source can be moved with -T".into(),
+            });
+        } else if self.sources.len() > 1 {
             if !target_exist {
-                errors.push(PlanError {
+                pending.errors.push(PlanError {
                     kind: ErrorKind::NotFound,
                     path: Some(self.target.clone()),
                     message: "Target does not exist".into(),
                 });
             } else if !target_is_dir {
-                errors.push(PlanError {
+                pending.errors.push(PlanError {
                     kind: ErrorKind::InvalidPath,
                     path: Some(self.target.clone()),
                     message: "Target must be a directory".into(),
@@ -55,8 +331,8 @@ impl super::traits::Planner for MvPlanner {
         }
 
         for src in self.sources.iter() {
-            if !src.exists() {
-                errors.push(PlanError {
+            if !fs.exists(src) {
+                pending.errors.push(PlanError {
                     kind: ErrorKind::NotFound,
                     path: Some(src.clone()),
                     message: "Source does not exist".into(),
@@ -64,7 +340,7 @@ impl super::traits::Planner for MvPlanner {
                 continue;
             }
 
-            if src.is_dir() {
+            if fs.is_dir(src) {
                 let dest_dir = if target_is_dir {
                     self.target.join(src.file_name().unwrap())
                 } else {
@@ -72,44 +348,69 @@ impl super::traits::Planner for MvPlanner {
                 };
 
                 let mut dirs_to_delete = vec![];
-                for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
-                    let entry_path = entry.path();
+                let mut kept = HashSet::new();
+                // Kept directories whose destination is only created once
+                // something inside them is moved
+                let mut kept_dirs = HashMap::new();
+                for entry in fs.walk(src, false) {
+                    let entry_path = entry.path.as_path();
                     let relative_path = entry_path.strip_prefix(src).unwrap();
                     let dest_path = dest_dir.join(relative_path);
+                    let is_dir = entry.metadata.is_dir();
 
-                    if entry.file_type().is_dir() {
-                        actions.push(Action::Create {
-                            path: dest_path.clone(),
-                            kind: FsObjectKind::Directory,
-                        });
-                        if !dest_path.exists() {
-                            summary.dirs_created += 1;
+                    if self.filter.keeps(fs, src, entry_path, is_dir) {
+                        if is_dir {
+                            pending.summary.dirs_kept += 1;
+                            kept_dirs.insert(entry_path.to_path_buf(), dest_path);
+                        } else {
+                            pending.summary.files_kept += 1;
                         }
-                        dirs_to_delete.push(entry_path.to_path_buf());
+                        PathFilter::mark_parents(&mut kept, src, entry_path);
+                        continue;
+                    }
+
+                    // With --gitignored-only, moved entries can sit inside
+                    // directories that stay
+                    let kept_parents: Vec<PathBuf> = entry_path
+                        .ancestors()
+                        .skip(1)
+                        .take_while(|parent| parent.starts_with(src))
+                        .filter_map(|parent| kept_dirs.remove(parent))
+                        .collect();
+                    for parent in kept_parents.into_iter().rev() {
+                        Self::create_dir(fs, &mut pending, parent);
+                    }
+
+                    if is_dir {
+                        Self::create_dir(fs, &mut pending, dest_path);
+                        dirs_to_delete.push((entry_path.to_path_buf(), entry.metadata.modified));
                     } else {
-                        let overwrite = dest_path.exists();
-                        if overwrite {
-                            warnings.push(PlanWarning {
-                                kind: WarningKind::Overwrite,
-                                paths: vec![dest_path.clone()],
-                                message: "Dest will be overwrite".into(),
-                            });
+                        let moved = self.move_file(
+                            fs,
+                            &mut pending,
+                            entry_path.to_path_buf(),
+                            dest_path,
+                            entry.metadata,
+                        );
+                        // Skipped files keep their source directories too
+                        if !moved {
+                            PathFilter::mark_parents(&mut kept, src, entry_path);
                         }
-                        actions.push(Action::Move {
-                            from: entry_path.to_path_buf(),
-                            to: dest_path,
-                            overwrite,
-                        });
-                        summary.files_moved += 1;
                     }
                 }
-                for dir in dirs_to_delete.iter().rev() {
-                    actions.push(Action::Delete {
-                        path: dir.clone(),
+                // Source directories still holding kept entries stay in place
+                for (dir, modified) in dirs_to_delete.into_iter().rev() {
+                    if kept.contains(&dir) {
+                        continue;
+                    }
+                    pending.actions.push(Action::Delete {
+                        path: dir,
                         kind: FsObjectKind::Directory,
                         recursive: false,
+                        size: 0,
+                        modified,
                     });
-                    summary.dirs_deleted += 1;
+                    pending.summary.dirs_deleted += 1;
                 }
             } else {
                 let dest = if self.sources.len() > 1 || target_is_dir {
@@ -119,7 +420,7 @@ impl super::traits::Planner for MvPlanner {
                 };
 
                 if src == &dest {
-                    errors.push(PlanError {
+                    pending.errors.push(PlanError {
                         kind: ErrorKind::InvalidPath,
                         path: Some(src.clone()),
                         message: "Source and destination are the same".into(),
@@ -127,25 +428,24 @@ impl super::traits::Planner for MvPlanner {
                     continue;
                 }
 
-                let overwrite = dest.exists();
-                if overwrite {
-                    warnings.push(PlanWarning {
-                        kind: WarningKind::Overwrite,
-                        paths: vec![dest.clone()],
-                        message: "Dest will be overwrite".into(),
-                    });
-                }
-
-                actions.push(Action::Move {
-                    from: src.clone(),
-                    to: dest,
-                    overwrite,
-                });
-
-                summary.files_moved += 1;
+                // The source exists, so only a race leaves it without metadata
+                let Ok(metadata) = fs.symlink_metadata(src) else {
+                    continue;
+                };
+                self.move_file(fs, &mut pending, src.clone(), dest, metadata);
             }
         }
 
+        let Pending {
+            actions,
+            mut warnings,
+            errors,
+            mut summary,
+            skipped,
+            ..
+        } = pending;
+        warnings.extend(VcsCheck::warnings(fs, &actions));
+
         summary.warnings = warnings.len();
         summary.errors = errors.len();
 
@@ -159,6 +459,9 @@ impl super::traits::Planner for MvPlanner {
             warnings,
             errors,
             summary,
+            patterns: Vec::new(),
+            skipped,
+            omitted: 0,
         }
     }
 }
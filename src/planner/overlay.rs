@@ -0,0 +1,148 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    planner::action::FsObjectKind,
+    vfs::traits::{FileSystem, Metadata},
+};
+
+#[doc = "An entry as seen through the overlay"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub kind: FsObjectKind,
+    /// Size in bytes, zero for directories
+    pub size: u64,
+}
+
+impl From<Metadata> for Entry {
+    fn from(metadata: Metadata) -> Self {
+        Self {
+            kind: metadata.kind,
+            size: metadata.len,
+        }
+    }
+}
+
+#[doc = "Simulated changes layered over a file system"]
+pub struct Overlay<'a> {
+    base: &'a dyn FileSystem,
+    /// Changed paths: `Some` when created or replaced, `None` when removed
+    changes: BTreeMap<PathBuf, Option<Entry>>,
+}
+
+impl<'a> Overlay<'a> {
+    pub fn new(base: &'a dyn FileSystem) -> Self {
+        Self {
+            base,
+            changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<Entry> {
+        if let Some(change) = self.changes.get(path) {
+            return *change;
+        }
+
+        // Anything below a removed or replaced directory is gone too
+        for ancestor in path.ancestors().skip(1) {
+            match self.changes.get(ancestor) {
+                Some(Some(entry)) if entry.kind == FsObjectKind::Directory => break,
+                Some(_) => return None,
+                None => {}
+            }
+        }
+
+        self.base.symlink_metadata(path).ok().map(Entry::from)
+    }
+
+    /// Modification time of an entry the overlay has not touched, neither
+    /// itself nor through one of its parents
+    pub fn modified(&self, path: &Path) -> Option<SystemTime> {
+        if path.ancestors().any(|p| self.changes.contains_key(p)) {
+            return None;
+        }
+        self.base.symlink_metadata(path).ok()?.modified
+    }
+
+    pub fn exists(&self, path: &Path) -> bool {
+        self.get(path).is_some()
+    }
+
+    pub fn is_dir(&self, path: &Path) -> bool {
+        self.get(path)
+            .is_some_and(|entry| entry.kind == FsObjectKind::Directory)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, entry: Entry) {
+        self.changes.insert(path, Some(entry));
+    }
+
+    pub fn remove(&mut self, path: PathBuf) {
+        self.changes.insert(path, None);
+    }
+
+    /// Paths whose entry now differs from the underlying file system, with
+    /// what they hold now, sorted by path
+    pub fn changes(&self) -> Vec<(PathBuf, Option<Entry>)> {
+        self.changes
+            .keys()
+            .filter_map(|path| {
+                let now = self.get(path);
+                let before = self.base.symlink_metadata(path).ok().map(Entry::from);
+                (now != before).then(|| (path.clone(), now))
+            })
+            .collect()
+    }
+
+    /// Names of the entries directly inside `dir`, sorted
+    pub fn children(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut children = self.base.read_dir(dir).unwrap_or_default();
+
+        for (path, _) in self.changes.range(dir.to_path_buf()..) {
+            if !path.starts_with(dir) {
+                break;
+            }
+            if path.parent() == Some(dir) {
+                children.push(path.clone());
+            }
+        }
+
+        children.sort();
+        children.dedup();
+        children.retain(|path| self.exists(path));
+        children
+    }
+
+    /// Every entry below `dir`, parents before their contents
+    pub fn descendants(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        for child in self.children(dir) {
+            let is_dir = self.is_dir(&child);
+            found.push(child.clone());
+            if is_dir {
+                found.extend(self.descendants(&child));
+            }
+        }
+        found
+    }
+}
+
+/// Resolves `path` against `base` and removes `.` and `..` lexically
+pub fn normalize(base: &Path, path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
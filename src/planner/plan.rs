@@ -1,20 +1,51 @@
+use std::fmt;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use serde::{Deserialize, Serialize};
+
 use crate::planner::action::Action;
 
 #[doc = "Plan data"]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Plan {
     pub metadata: PlanMetadata,
     pub actions: Vec<Action>,
     pub warnings: Vec<PlanWarning>,
     pub errors: Vec<PlanError>,
     pub summary: PlanSummary,
+    /// Glob patterns expanded into the planned paths
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<PatternMatch>,
+    /// Entries the planner left alone on purpose, such as `mv -n` sources
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedEntry>,
+    /// Actions of a streamed plan left out of `actions`, which then only
+    /// holds a preview
+    #[serde(skip_serializing_if = "is_zero")]
+    pub omitted: usize,
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+impl Plan {
+    pub fn add_warning(&mut self, warning: PlanWarning) {
+        self.warnings.push(warning);
+        self.summary.warnings = self.warnings.len();
+    }
+}
+
+#[doc = "Paths a glob pattern expanded to"]
+#[derive(Debug, Serialize)]
+pub struct PatternMatch {
+    pub pattern: String,
+    pub matches: Vec<PathBuf>,
 }
 
 #[doc = "Command metadata"]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct PlanMetadata {
     pub command: CommandKind,
     pub working_dir: PathBuf,
@@ -22,15 +53,18 @@ pub struct PlanMetadata {
 }
 
 #[doc = "Command kind simplified for metadata"]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CommandKind {
     Touch,
     Mv,
     Rm,
+    Script,
 }
 
 #[doc = "Affected summary"]
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+// Counts missing from older audit records read back as zero
+#[serde(default)]
 pub struct PlanSummary {
     pub files_deleted: usize,
     pub dirs_deleted: usize,
@@ -38,35 +72,77 @@ pub struct PlanSummary {
     pub dirs_created: usize,
     pub files_moved: usize,
     pub files_modified: usize,
+    pub files_copied: usize,
+    pub links_created: usize,
+    /// Entries left in place by include/exclude filters
+    pub files_kept: usize,
+    pub dirs_kept: usize,
+    /// Entries not moved because of their destination
+    pub files_skipped: usize,
+    /// Overwritten destinations renamed to their backups
+    pub files_backed_up: usize,
+    pub bytes_deleted: u64,
+    pub bytes_moved: u64,
+    pub bytes_copied: u64,
+    pub bytes_overwritten: u64,
     pub warnings: usize,
     pub errors: usize,
 }
 
+#[doc = "An entry that was not planned, and why"]
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub destination: PathBuf,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SkipReason {
+    /// The destination exists and must not be overwritten
+    DestinationExists,
+    /// The destination is as new as the source, or newer
+    DestinationNotOlder,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DestinationExists => write!(f, "destination exists"),
+            Self::DestinationNotOlder => write!(f, "destination is not older"),
+        }
+    }
+}
+
 #[doc = "Command Warning"]
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlanWarning {
     pub kind: WarningKind,
     pub paths: Vec<PathBuf>,
     pub message: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WarningKind {
     Overwrite,
     RecursiveDelete,
     LargeOperation,
     PermissionRisk,
+    VcsTracked,
+    UncommittedChanges,
+    EmptyPattern,
+    Unpreviewed,
 }
 
 #[doc = "Command Error"]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct PlanError {
     pub kind: ErrorKind,
     pub path: Option<PathBuf>,
     pub message: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub enum ErrorKind {
     NotFound,
     PermissionDenied,
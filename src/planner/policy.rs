@@ -0,0 +1,61 @@
+use crate::{error::Error, planner::plan::Plan};
+
+#[doc = "Limits a plan must stay within before it may be executed"]
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    pub max_actions: Option<usize>,
+    /// Bytes deleted, moved or copied
+    pub max_bytes: Option<u64>,
+    pub fail_on: FailOn,
+}
+
+#[doc = "Least severe finding that rejects a plan"]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailOn {
+    #[default]
+    Errors,
+    Warnings,
+}
+
+impl Policy {
+    /// Checks errors first, then limits, then warnings when they count
+    pub fn check(&self, plan: &Plan) -> Result<(), Error> {
+        if !plan.errors.is_empty() {
+            return Err(Error::PlanInvalid(format!(
+                "Plan has {} errors",
+                plan.errors.len()
+            )));
+        }
+
+        // Streamed plans only keep a preview of their actions
+        let actions = plan.actions.len() + plan.omitted;
+        if let Some(max) = self.max_actions
+            && actions > max
+        {
+            return Err(Error::LimitExceeded(format!(
+                "Plan has {} actions, more than the limit of {}",
+                actions, max
+            )));
+        }
+
+        let summary = &plan.summary;
+        let bytes = summary.bytes_deleted + summary.bytes_moved + summary.bytes_copied;
+        if let Some(max) = self.max_bytes
+            && bytes > max
+        {
+            return Err(Error::LimitExceeded(format!(
+                "Plan affects {} bytes, more than the limit of {}",
+                bytes, max
+            )));
+        }
+
+        if self.fail_on == FailOn::Warnings && !plan.warnings.is_empty() {
+            return Err(Error::PolicyViolation(format!(
+                "Plan has {} warnings",
+                plan.warnings.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
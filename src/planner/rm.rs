@@ -1,13 +1,15 @@
-use std::path::PathBuf;
-use std::{fs, time::SystemTime};
-
-use walkdir::WalkDir;
+use std::collections::HashSet;
+use std::iter;
+use std::path::{Path, PathBuf};
 
+use crate::planner::filter::PathFilter;
 use crate::planner::plan::CommandKind;
+use crate::planner::stream::{PlanBuilder, PlanItem, StreamingPlanner};
 use crate::planner::{
     action::{Action, FsObjectKind},
-    plan::{ErrorKind, Plan, PlanError, PlanMetadata, PlanSummary, PlanWarning, WarningKind},
+    plan::{ErrorKind, Plan, PlanError, PlanWarning, WarningKind},
 };
+use crate::vfs::traits::FileSystem;
 
 #[doc = "Planner for `rm`"]
 pub struct RmPlanner {
@@ -15,6 +17,7 @@ pub struct RmPlanner {
     pub recursive: bool,
     pub force: bool,
     pub cwd: PathBuf,
+    pub filter: PathFilter,
 }
 
 impl RmPlanner {
@@ -24,119 +27,144 @@ impl RmPlanner {
             recursive,
             force,
             cwd,
+            filter: PathFilter::default(),
         }
     }
+
+    pub fn with_filter(mut self, filter: PathFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 impl super::traits::Planner for RmPlanner {
-    fn plan(&self) -> Plan {
-        let mut actions = Vec::new();
-        let mut warnings = Vec::new();
-        let mut errors = Vec::new();
-        let mut summary = PlanSummary::default();
-
-        for target in self.targets.iter() {
-            if !target.exists() {
-                if !self.force {
-                    errors.push(PlanError {
-                        kind: ErrorKind::NotFound,
-                        path: Some(target.clone()),
-                        message: "No such file or directory".into(),
-                    });
-                }
-                continue;
+    /// Walks each directory as a whole, which [`FileSystem::walk`] may do on
+    /// several threads
+    fn plan(&self, fs: &dyn FileSystem) -> Plan {
+        let mut builder = PlanBuilder::new(fs, CommandKind::Rm, self.cwd.clone());
+        for item in self.items(fs, false) {
+            builder.push(item);
+        }
+        builder.finish()
+    }
+}
+
+impl StreamingPlanner for RmPlanner {
+    /// Walks directories with [`FileSystem::walk_iter`] so the tree is never
+    /// held in memory
+    fn stream<'a>(&'a self, fs: &'a dyn FileSystem) -> Box<dyn Iterator<Item = PlanItem> + 'a> {
+        self.items(fs, true)
+    }
+}
+
+impl RmPlanner {
+    fn items<'a>(
+        &'a self,
+        fs: &'a dyn FileSystem,
+        streaming: bool,
+    ) -> Box<dyn Iterator<Item = PlanItem> + 'a> {
+        Box::new(
+            self.targets
+                .iter()
+                .flat_map(move |target| self.plan_target(fs, target, streaming)),
+        )
+    }
+
+    fn plan_target<'a>(
+        &'a self,
+        fs: &'a dyn FileSystem,
+        target: &'a Path,
+        streaming: bool,
+    ) -> Box<dyn Iterator<Item = PlanItem> + 'a> {
+        if !fs.exists(target) {
+            if self.force {
+                return Box::new(iter::empty());
             }
+            return Self::one(PlanItem::Error(PlanError {
+                kind: ErrorKind::NotFound,
+                path: Some(target.to_path_buf()),
+                message: "No such file or directory".into(),
+            }));
+        }
 
-            let metadata = match fs::symlink_metadata(target) {
-                Ok(m) => m,
-                Err(_) => {
-                    errors.push(PlanError {
-                        kind: ErrorKind::PermissionDenied,
-                        path: Some(target.clone()),
-                        message: "Permission denied".into(),
-                    });
-                    continue;
-                }
-            };
-
-            if metadata.is_dir() {
-                if self.recursive {
-                    warnings.push(PlanWarning {
-                        kind: WarningKind::RecursiveDelete,
-                        paths: vec![target.clone()],
-                        message: "Recursive directory deletion".into(),
-                    });
-
-                    for entry in WalkDir::new(target)
-                        .contents_first(true)
-                        .into_iter()
-                        .filter_map(Result::ok)
-                    {
-                        let path = entry.path().to_path_buf();
-                        let kind = if entry.file_type().is_dir() {
-                            summary.dirs_deleted += 1;
-                            FsObjectKind::Directory
-                        } else {
-                            summary.files_deleted += 1;
-                            FsObjectKind::File
-                        };
-
-                        actions.push(Action::Delete {
-                            path,
-                            kind,
-                            recursive: false,
-                        });
-                    }
-                } else {
-                    // Not recursive, check if directory is empty
-                    match std::fs::read_dir(target) {
-                        Ok(mut dir) => {
-                            if dir.next().is_some() {
-                                // Not empty
-                                errors.push(PlanError {
-                                    kind: ErrorKind::Unsupported,
-                                    path: Some(target.clone()),
-                                    message: "Is a directory (use -r)".into(),
-                                });
-                                continue;
-                            }
-                            // Is empty, fallthrough to delete
-                        }
-                        Err(_) => {
-                            // Could be a permission error, let the delete action fail later
-                        }
-                    }
-                    // This is for an empty directory
-                    summary.dirs_deleted += 1;
-                    actions.push(Action::Delete {
-                        path: target.clone(),
-                        kind: FsObjectKind::Directory,
-                        recursive: false,
-                    });
-                }
-            } else {
-                summary.files_deleted += 1;
-                actions.push(Action::Delete {
-                    path: target.clone(),
-                    kind: FsObjectKind::File,
-                    recursive: false,
-                });
+        let metadata = match fs.symlink_metadata(target) {
+            Ok(m) => m,
+            Err(_) => {
+                return Self::one(PlanItem::Error(PlanError {
+                    kind: ErrorKind::PermissionDenied,
+                    path: Some(target.to_path_buf()),
+                    message: "Permission denied".into(),
+                }));
             }
+        };
+
+        if !metadata.is_dir() {
+            return Self::one(PlanItem::Action(Action::Delete {
+                path: target.to_path_buf(),
+                kind: metadata.kind,
+                recursive: false,
+                size: metadata.len,
+                modified: metadata.modified,
+            }));
         }
 
-        summary.warnings = warnings.len();
-        summary.errors = errors.len();
-
-        Plan {
-            metadata: PlanMetadata {
-                command: CommandKind::Rm,
-                working_dir: self.cwd.clone(),
-                created_at: SystemTime::now(),
-            },
-            actions,
-            warnings,
-            errors,
-            summary,
+        if !self.recursive {
+            // Not recursive, check if directory is empty
+            if let Ok(children) = fs.read_dir(target)
+                && !children.is_empty()
+            {
+                return Self::one(PlanItem::Error(PlanError {
+                    kind: ErrorKind::Unsupported,
+                    path: Some(target.to_path_buf()),
+                    message: "Is a directory (use -r)".into(),
+                }));
+            }
+            // An unreadable directory could be a permission error, let the
+            // delete action fail later
+            return Self::one(PlanItem::Action(Action::Delete {
+                path: target.to_path_buf(),
+                kind: FsObjectKind::Directory,
+                recursive: false,
+                size: 0,
+                modified: metadata.modified,
+            }));
         }
+
+        let warning = PlanItem::Warning(PlanWarning {
+            kind: WarningKind::RecursiveDelete,
+            paths: vec![target.to_path_buf()],
+            message: "Recursive directory deletion".into(),
+        });
+
+        let walk = if streaming {
+            fs.walk_iter(target, true)
+        } else {
+            Box::new(fs.walk(target, true).into_iter())
+        };
+        let mut kept = HashSet::new();
+        let entries = walk.map(move |entry| {
+            let path = entry.path;
+            let is_dir = entry.metadata.is_dir();
+
+            // Directories holding kept entries must stay as well
+            if kept.contains(&path) || self.filter.keeps(fs, target, &path, is_dir) {
+                PathFilter::mark_parents(&mut kept, target, &path);
+                return PlanItem::Kept { is_dir };
+            }
+
+            PlanItem::Action(Action::Delete {
+                path,
+                kind: entry.metadata.kind,
+                recursive: false,
+                size: if is_dir { 0 } else { entry.metadata.len },
+                modified: entry.metadata.modified,
+            })
+        });
+
+        Box::new(iter::once(warning).chain(entries))
+    }
+
+    fn one<'a>(item: PlanItem) -> Box<dyn Iterator<Item = PlanItem> + 'a> {
+        Box::new(iter::once(item))
     }
 }
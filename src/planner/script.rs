@@ -0,0 +1,836 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        overlay::{Entry, Overlay, normalize},
+        plan::{
+            CommandKind, ErrorKind, Plan, PlanError, PlanMetadata, PlanSummary, PlanWarning,
+            WarningKind,
+        },
+        vcs::VcsCheck,
+    },
+    vfs::traits::FileSystem,
+};
+
+/// Shell operators that make a line more than a sequence of simple commands
+const UNSUPPORTED_OPERATORS: [&str; 7] = ["||", "|", ">", ">>", "<", "&", "2>"];
+
+#[doc = "Planner for a shell script of `touch`/`mkdir`/`cp`/`mv`/`rm`/`ln` commands"]
+pub struct ScriptPlanner {
+    pub source: String,
+    pub cwd: PathBuf,
+}
+
+impl ScriptPlanner {
+    pub fn new(source: String, cwd: PathBuf) -> Self {
+        Self { source, cwd }
+    }
+}
+
+/// Flags and operands of one command
+#[derive(Debug, Default)]
+struct Invocation {
+    flags: Vec<char>,
+    operands: Vec<PathBuf>,
+}
+
+impl Invocation {
+    fn has(&self, flag: char) -> bool {
+        self.flags.contains(&flag)
+    }
+}
+
+/// Plan under construction while the script is replayed
+struct Replay<'a> {
+    overlay: Overlay<'a>,
+    cwd: PathBuf,
+    line: usize,
+    actions: Vec<Action>,
+    warnings: Vec<PlanWarning>,
+    errors: Vec<PlanError>,
+    summary: PlanSummary,
+}
+
+impl super::traits::Planner for ScriptPlanner {
+    fn plan(&self, fs: &dyn FileSystem) -> Plan {
+        let mut replay = Replay {
+            overlay: Overlay::new(fs),
+            cwd: self.cwd.clone(),
+            line: 0,
+            actions: Vec::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            summary: PlanSummary::default(),
+        };
+
+        for (line, text) in Self::logical_lines(&self.source) {
+            replay.line = line;
+            replay.run_line(&text);
+        }
+
+        let Replay {
+            actions,
+            mut warnings,
+            errors,
+            mut summary,
+            ..
+        } = replay;
+
+        warnings.extend(VcsCheck::warnings(fs, &actions));
+
+        summary.warnings = warnings.len();
+        summary.errors = errors.len();
+
+        Plan {
+            metadata: PlanMetadata {
+                command: CommandKind::Script,
+                working_dir: self.cwd.clone(),
+                created_at: SystemTime::now(),
+            },
+            actions,
+            warnings,
+            errors,
+            summary,
+            patterns: Vec::new(),
+            skipped: Vec::new(),
+            omitted: 0,
+        }
+    }
+}
+
+impl ScriptPlanner {
+    /// Joins `\`-continued lines and drops blanks and comments, keeping the
+    /// number of the line each command starts on.
+    fn logical_lines(source: &str) -> Vec<(usize, String)> {
+        let mut lines = Vec::new();
+        let mut pending: Option<(usize, String)> = None;
+
+        for (index, raw) in source.lines().enumerate() {
+            let (start, mut text) = pending.take().unwrap_or((index + 1, String::new()));
+            text.push_str(raw.trim());
+
+            if let Some(stripped) = text.strip_suffix('\\') {
+                let mut stripped = stripped.to_string();
+                stripped.push(' ');
+                pending = Some((start, stripped));
+                continue;
+            }
+
+            if !text.is_empty() && !text.starts_with('#') {
+                lines.push((start, text));
+            }
+        }
+
+        if let Some(last) = pending {
+            lines.push(last);
+        }
+
+        lines
+    }
+}
+
+impl Replay<'_> {
+    fn run_line(&mut self, text: &str) {
+        let words = match shell_words::split(text) {
+            Ok(words) => words,
+            Err(err) => {
+                self.error(
+                    ErrorKind::Unsupported,
+                    None,
+                    format!("cannot parse: {}", err),
+                );
+                return;
+            }
+        };
+
+        if let Some(op) = words
+            .iter()
+            .find(|w| UNSUPPORTED_OPERATORS.contains(&w.as_str()))
+        {
+            self.error(
+                ErrorKind::Unsupported,
+                None,
+                format!("`{}` is not supported", op),
+            );
+            return;
+        }
+
+        if let Some(op) = attached_operator(text) {
+            self.error(
+                ErrorKind::Unsupported,
+                None,
+                format!("`{}` is not supported", op),
+            );
+            return;
+        }
+
+        // `a && b` and `a; b` run one after the other
+        let mut command = Vec::new();
+        for word in words {
+            if word == "&&" || word == ";" {
+                self.run_command(&command);
+                command.clear();
+            } else if let Some(word) = word.strip_suffix(';') {
+                command.push(word.to_string());
+                self.run_command(&command);
+                command.clear();
+            } else {
+                command.push(word);
+            }
+        }
+        self.run_command(&command);
+    }
+
+    fn run_command(&mut self, words: &[String]) {
+        let Some((name, args)) = words.split_first() else {
+            return;
+        };
+
+        let allowed = match name.as_str() {
+            "touch" | "cd" => "",
+            "mkdir" => "pv",
+            "cp" => "rRfv",
+            "mv" => "fv",
+            "rm" => "rRfv",
+            "ln" => "sfv",
+            _ => {
+                self.warnings.push(PlanWarning {
+                    kind: WarningKind::Unpreviewed,
+                    paths: Vec::new(),
+                    message: format!("line {}: `{}` is not previewed", self.line, name),
+                });
+                return;
+            }
+        };
+
+        let invocation = match Self::parse_args(args, allowed) {
+            Ok(invocation) => invocation,
+            Err(flag) => {
+                self.error(
+                    ErrorKind::Unsupported,
+                    None,
+                    format!("{}: unsupported option `{}`", name, flag),
+                );
+                return;
+            }
+        };
+
+        match name.as_str() {
+            "cd" => self.cd(&invocation),
+            "touch" => self.touch(&invocation),
+            "mkdir" => self.mkdir(&invocation),
+            "cp" => self.cp(&invocation),
+            "mv" => self.mv(&invocation),
+            "rm" => self.rm(&invocation),
+            "ln" => self.ln(&invocation),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Splits short flag clusters like `-rf` and the long forms of `allowed`
+    fn parse_args(args: &[String], allowed: &str) -> Result<Invocation, String> {
+        let mut invocation = Invocation::default();
+        let mut operands_only = false;
+
+        for arg in args {
+            if operands_only || arg == "-" || !arg.starts_with('-') {
+                invocation.operands.push(PathBuf::from(arg));
+                continue;
+            }
+
+            let flags: Vec<char> = match arg.as_str() {
+                "--" => {
+                    operands_only = true;
+                    continue;
+                }
+                "--parents" => vec!['p'],
+                "--recursive" => vec!['r'],
+                "--force" => vec!['f'],
+                "--symbolic" => vec!['s'],
+                "--verbose" => vec!['v'],
+                long if long.starts_with("--") => return Err(long.to_string()),
+                short => short.chars().skip(1).collect(),
+            };
+
+            for flag in flags {
+                if !allowed.contains(flag) {
+                    return Err(format!("-{}", flag));
+                }
+                invocation.flags.push(if flag == 'R' { 'r' } else { flag });
+            }
+        }
+
+        Ok(invocation)
+    }
+
+    fn cd(&mut self, invocation: &Invocation) {
+        let [dir] = invocation.operands.as_slice() else {
+            self.error(
+                ErrorKind::Unsupported,
+                None,
+                "cd: exactly one directory is supported".into(),
+            );
+            return;
+        };
+
+        let dir = self.resolve(dir);
+        if self.overlay.is_dir(&dir) {
+            self.cwd = dir;
+        } else {
+            self.error(
+                ErrorKind::NotFound,
+                Some(dir),
+                "cd: No such directory".into(),
+            );
+        }
+    }
+
+    fn touch(&mut self, invocation: &Invocation) {
+        for path in invocation.operands.iter() {
+            let path = self.resolve(path);
+
+            if let Some(entry) = self.overlay.get(&path) {
+                let modified = self.overlay.modified(&path);
+                self.actions.push(Action::Modify {
+                    path,
+                    kind: entry.kind,
+                    description: "Update modification time".into(),
+                    modified,
+                });
+                self.summary.files_modified += 1;
+            } else if self.parent_is_dir(&path) {
+                self.overlay
+                    .insert(path.clone(), Self::entry(FsObjectKind::File, 0));
+                self.actions.push(Action::Create {
+                    path,
+                    kind: FsObjectKind::File,
+                });
+                self.summary.files_created += 1;
+            } else {
+                self.error(
+                    ErrorKind::NotFound,
+                    Some(path),
+                    "touch: No such file or directory".into(),
+                );
+            }
+        }
+    }
+
+    fn mkdir(&mut self, invocation: &Invocation) {
+        let parents = invocation.has('p');
+
+        for path in invocation.operands.iter() {
+            let path = self.resolve(path);
+
+            if self.overlay.exists(&path) {
+                if !(parents && self.overlay.is_dir(&path)) {
+                    self.error(
+                        ErrorKind::InvalidPath,
+                        Some(path),
+                        "mkdir: File exists".into(),
+                    );
+                }
+                continue;
+            }
+
+            let missing: Vec<PathBuf> = path
+                .ancestors()
+                .take_while(|p| !self.overlay.exists(p))
+                .map(Path::to_path_buf)
+                .collect();
+
+            if let Some(existing) = path.ancestors().nth(missing.len())
+                && !self.overlay.is_dir(existing)
+            {
+                self.error(
+                    ErrorKind::InvalidPath,
+                    Some(path),
+                    "mkdir: Not a directory".into(),
+                );
+                continue;
+            }
+
+            if missing.len() > 1 && !parents {
+                self.error(
+                    ErrorKind::NotFound,
+                    Some(path),
+                    "mkdir: No such file or directory".into(),
+                );
+                continue;
+            }
+
+            for dir in missing.into_iter().rev() {
+                self.create_dir(dir);
+            }
+        }
+    }
+
+    fn cp(&mut self, invocation: &Invocation) {
+        let Some((sources, dest)) = self.sources_and_dest("cp", invocation) else {
+            return;
+        };
+
+        for src in sources {
+            let Some(entry) = self.source_entry("cp", &src) else {
+                continue;
+            };
+            let Some((target, overwrite)) = self.target_for("cp", &src, &dest, entry) else {
+                continue;
+            };
+
+            if entry.kind != FsObjectKind::Directory {
+                self.copy_file(src, target, entry, overwrite);
+                continue;
+            }
+
+            if !invocation.has('r') {
+                self.error(
+                    ErrorKind::Unsupported,
+                    Some(src),
+                    "cp: omitting directory (use -r)".into(),
+                );
+                continue;
+            }
+
+            if overwrite.is_none() {
+                self.create_dir(target.clone());
+            }
+            for path in self.overlay.descendants(&src) {
+                let Some(entry) = self.overlay.get(&path) else {
+                    continue;
+                };
+                let to = target.join(path.strip_prefix(&src).unwrap());
+                if entry.kind == FsObjectKind::Directory {
+                    if !self.overlay.exists(&to) {
+                        self.create_dir(to);
+                    }
+                } else {
+                    let overwrite = self.overlay.get(&to);
+                    self.copy_file(path, to, entry, overwrite);
+                }
+            }
+        }
+    }
+
+    fn mv(&mut self, invocation: &Invocation) {
+        let Some((sources, dest)) = self.sources_and_dest("mv", invocation) else {
+            return;
+        };
+
+        for src in sources {
+            let Some(entry) = self.source_entry("mv", &src) else {
+                continue;
+            };
+            let Some((target, overwrite)) = self.target_for("mv", &src, &dest, entry) else {
+                continue;
+            };
+
+            if target.starts_with(&src) {
+                self.error(
+                    ErrorKind::InvalidPath,
+                    Some(src),
+                    "mv: cannot move a directory into itself".into(),
+                );
+                continue;
+            }
+
+            let mut size = entry.size;
+            if entry.kind == FsObjectKind::Directory {
+                for path in self.overlay.descendants(&src) {
+                    if let Some(moved) = self.overlay.get(&path) {
+                        size += moved.size;
+                        let to = target.join(path.strip_prefix(&src).unwrap());
+                        self.overlay.insert(to, moved);
+                        self.overlay.remove(path);
+                    }
+                }
+            }
+
+            if let Some(existing) = overwrite {
+                self.overwrite_warning(&target);
+                self.summary.bytes_overwritten += existing.size;
+            }
+
+            let modified = self.overlay.modified(&src);
+            self.overlay.remove(src.clone());
+            self.overlay.insert(target.clone(), entry);
+            self.actions.push(Action::Move {
+                from: src,
+                to: target,
+                kind: entry.kind,
+                overwrite,
+                size,
+                modified,
+                backup: false,
+            });
+            self.summary.files_moved += 1;
+            self.summary.bytes_moved += size;
+        }
+    }
+
+    fn rm(&mut self, invocation: &Invocation) {
+        for path in invocation.operands.iter() {
+            let path = self.resolve(path);
+
+            let Some(entry) = self.overlay.get(&path) else {
+                if !invocation.has('f') {
+                    self.error(
+                        ErrorKind::NotFound,
+                        Some(path),
+                        "rm: No such file or directory".into(),
+                    );
+                }
+                continue;
+            };
+
+            if entry.kind == FsObjectKind::Directory {
+                if !invocation.has('r') {
+                    self.error(
+                        ErrorKind::Unsupported,
+                        Some(path),
+                        "rm: Is a directory (use -r)".into(),
+                    );
+                    continue;
+                }
+
+                for child in self.overlay.descendants(&path).into_iter().rev() {
+                    if let Some(entry) = self.overlay.get(&child) {
+                        self.delete(child, entry);
+                    }
+                }
+            }
+
+            self.delete(path, entry);
+        }
+    }
+
+    fn ln(&mut self, invocation: &Invocation) {
+        let symbolic = invocation.has('s');
+
+        let links: Vec<(PathBuf, PathBuf)> = match invocation.operands.as_slice() {
+            [] => {
+                self.error(ErrorKind::InvalidPath, None, "ln: missing operand".into());
+                return;
+            }
+            [target] => vec![(target.clone(), self.resolve(Path::new(".")))],
+            [targets @ .., dest] => {
+                let dest = self.resolve(dest);
+                if targets.len() > 1 && !self.overlay.is_dir(&dest) {
+                    self.error(
+                        ErrorKind::InvalidPath,
+                        Some(dest),
+                        "ln: target is not a directory".into(),
+                    );
+                    return;
+                }
+                targets.iter().map(|t| (t.clone(), dest.clone())).collect()
+            }
+        };
+
+        for (target, dest) in links {
+            let link = if self.overlay.is_dir(&dest) {
+                match target.file_name() {
+                    Some(name) => dest.join(name),
+                    None => dest,
+                }
+            } else {
+                dest
+            };
+
+            // Symlink targets are kept verbatim, hard links need the file
+            let entry = if symbolic {
+                Self::entry(FsObjectKind::Symlink, 0)
+            } else {
+                let resolved = self.resolve(&target);
+                match self.overlay.get(&resolved) {
+                    Some(entry) if entry.kind != FsObjectKind::Directory => entry,
+                    Some(_) => {
+                        self.error(
+                            ErrorKind::Unsupported,
+                            Some(resolved),
+                            "ln: hard link not allowed for directory".into(),
+                        );
+                        continue;
+                    }
+                    None => {
+                        self.error(
+                            ErrorKind::NotFound,
+                            Some(resolved),
+                            "ln: No such file or directory".into(),
+                        );
+                        continue;
+                    }
+                }
+            };
+            let target = if symbolic {
+                target
+            } else {
+                self.resolve(&target)
+            };
+
+            if let Some(existing) = self.overlay.get(&link) {
+                if !invocation.has('f') || existing.kind == FsObjectKind::Directory {
+                    self.error(ErrorKind::InvalidPath, Some(link), "ln: File exists".into());
+                    continue;
+                }
+                self.delete(link.clone(), existing);
+            }
+
+            self.overlay.insert(link.clone(), entry);
+            self.actions.push(Action::Link {
+                path: link,
+                target,
+                symbolic,
+            });
+            self.summary.links_created += 1;
+        }
+    }
+
+    /// Splits operands into sources and destination the way `cp`/`mv` do
+    fn sources_and_dest(
+        &mut self,
+        name: &str,
+        invocation: &Invocation,
+    ) -> Option<(Vec<PathBuf>, PathBuf)> {
+        let [sources @ .., dest] = invocation.operands.as_slice() else {
+            self.error(
+                ErrorKind::InvalidPath,
+                None,
+                format!("{}: missing operand", name),
+            );
+            return None;
+        };
+        if sources.is_empty() {
+            self.error(
+                ErrorKind::InvalidPath,
+                None,
+                format!("{}: missing destination", name),
+            );
+            return None;
+        }
+
+        let dest = self.resolve(dest);
+        if sources.len() > 1 && !self.overlay.is_dir(&dest) {
+            self.error(
+                ErrorKind::InvalidPath,
+                Some(dest),
+                format!("{}: target is not a directory", name),
+            );
+            return None;
+        }
+
+        let sources = sources.iter().map(|s| self.resolve(s)).collect();
+        Some((sources, dest))
+    }
+
+    fn source_entry(&mut self, name: &str, src: &Path) -> Option<Entry> {
+        let entry = self.overlay.get(src);
+        if entry.is_none() {
+            self.error(
+                ErrorKind::NotFound,
+                Some(src.to_path_buf()),
+                format!("{}: No such file or directory", name),
+            );
+        }
+        entry
+    }
+
+    /// Final path for `src` and whether it replaces an existing entry
+    fn target_for(
+        &mut self,
+        name: &str,
+        src: &Path,
+        dest: &Path,
+        entry: Entry,
+    ) -> Option<(PathBuf, Option<Entry>)> {
+        let target = if self.overlay.is_dir(dest) {
+            dest.join(src.file_name()?)
+        } else {
+            dest.to_path_buf()
+        };
+
+        if target == src {
+            self.error(
+                ErrorKind::InvalidPath,
+                Some(target),
+                format!("{}: Source and destination are the same", name),
+            );
+            return None;
+        }
+
+        if !self.parent_is_dir(&target) {
+            self.error(
+                ErrorKind::NotFound,
+                Some(target),
+                format!("{}: No such file or directory", name),
+            );
+            return None;
+        }
+
+        match self.overlay.get(&target) {
+            None => Some((target, None)),
+            Some(existing)
+                if (existing.kind == FsObjectKind::Directory)
+                    != (entry.kind == FsObjectKind::Directory) =>
+            {
+                self.error(
+                    ErrorKind::InvalidPath,
+                    Some(target),
+                    format!("{}: cannot overwrite a file with a directory or back", name),
+                );
+                None
+            }
+            Some(existing) if existing.kind == FsObjectKind::Directory && name == "mv" => {
+                if self.overlay.children(&target).is_empty() {
+                    Some((target, Some(existing)))
+                } else {
+                    self.error(
+                        ErrorKind::InvalidPath,
+                        Some(target),
+                        "mv: Directory not empty".into(),
+                    );
+                    None
+                }
+            }
+            Some(existing) => Some((target, Some(existing))),
+        }
+    }
+
+    fn copy_file(&mut self, from: PathBuf, to: PathBuf, entry: Entry, overwrite: Option<Entry>) {
+        if let Some(existing) = overwrite {
+            self.overwrite_warning(&to);
+            self.summary.bytes_overwritten += existing.size;
+        }
+
+        self.overlay
+            .insert(to.clone(), Self::entry(FsObjectKind::File, entry.size));
+        self.actions.push(Action::Copy {
+            modified: self.overlay.modified(&from),
+            from,
+            to,
+            overwrite,
+            size: entry.size,
+        });
+        self.summary.files_copied += 1;
+        self.summary.bytes_copied += entry.size;
+    }
+
+    fn create_dir(&mut self, path: PathBuf) {
+        self.overlay
+            .insert(path.clone(), Self::entry(FsObjectKind::Directory, 0));
+        self.actions.push(Action::Create {
+            path,
+            kind: FsObjectKind::Directory,
+        });
+        self.summary.dirs_created += 1;
+    }
+
+    fn delete(&mut self, path: PathBuf, entry: Entry) {
+        if entry.kind == FsObjectKind::Directory {
+            self.summary.dirs_deleted += 1;
+        } else {
+            self.summary.files_deleted += 1;
+        }
+        self.summary.bytes_deleted += entry.size;
+
+        let modified = self.overlay.modified(&path);
+        self.overlay.remove(path.clone());
+        self.actions.push(Action::Delete {
+            path,
+            kind: entry.kind,
+            recursive: false,
+            size: entry.size,
+            modified,
+        });
+    }
+
+    fn overwrite_warning(&mut self, path: &Path) {
+        self.warnings.push(PlanWarning {
+            kind: WarningKind::Overwrite,
+            paths: vec![path.to_path_buf()],
+            message: format!("line {}: Dest will be overwrite", self.line),
+        });
+    }
+
+    fn error(&mut self, kind: ErrorKind, path: Option<PathBuf>, message: String) {
+        self.errors.push(PlanError {
+            kind,
+            path,
+            message: format!("line {}: {}", self.line, message),
+        });
+    }
+
+    fn parent_is_dir(&self, path: &Path) -> bool {
+        path.parent().is_some_and(|p| self.overlay.is_dir(p))
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        normalize(&self.cwd, path)
+    }
+
+    fn entry(kind: FsObjectKind, size: u64) -> Entry {
+        Entry { kind, size }
+    }
+}
+
+/// Finds an operator written against other text in a word, like the `>` of
+/// `build>/dev/null`, skipping quoted and escaped characters.
+///
+/// Whole `&&` and `;` words and a trailing `;` are command separators and
+/// are left to the caller.
+fn attached_operator(text: &str) -> Option<String> {
+    let mut words: Vec<(String, Vec<bool>)> = Vec::new();
+    let mut word: Option<(String, Vec<bool>)> = None;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        if quote.is_none() && !escaped && c.is_whitespace() {
+            words.extend(word.take());
+            continue;
+        }
+
+        let (raw, operators) = word.get_or_insert_default();
+        raw.push(c);
+        operators.push(quote.is_none() && !escaped && "|&;<>".contains(c));
+
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (None | Some('"'), '\\') => escaped = true,
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+    }
+    words.extend(word);
+
+    for (raw, operators) in words {
+        if raw == "&&" || raw == ";" {
+            continue;
+        }
+
+        let chars: Vec<char> = raw.chars().collect();
+        let last = operators.len() - 1;
+        let found = operators
+            .iter()
+            .enumerate()
+            .position(|(i, &op)| op && !(i == last && chars[i] == ';'));
+
+        if let Some(start) = found {
+            return Some(
+                chars[start..]
+                    .iter()
+                    .zip(&operators[start..])
+                    .take_while(|(_, op)| **op)
+                    .map(|(c, _)| c)
+                    .collect(),
+            );
+        }
+    }
+
+    None
+}
@@ -0,0 +1,202 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::{CommandKind, Plan, PlanError, PlanMetadata, PlanSummary, PlanWarning},
+        vcs::VcsCheck,
+    },
+    vfs::traits::FileSystem,
+};
+
+/// Spool files created by this process, to keep their names apart
+static SPOOLS: AtomicUsize = AtomicUsize::new(0);
+
+#[doc = "One piece of a plan, as produced by a streaming planner"]
+#[derive(Debug)]
+pub enum PlanItem {
+    Action(Action),
+    Warning(PlanWarning),
+    Error(PlanError),
+    /// An entry left in place by a filter
+    Kept {
+        is_dir: bool,
+    },
+}
+
+#[doc = "Planner that yields its plan lazily instead of collecting it"]
+pub trait StreamingPlanner {
+    fn stream<'a>(&'a self, fs: &'a dyn FileSystem) -> Box<dyn Iterator<Item = PlanItem> + 'a>;
+}
+
+#[doc = "Collects plan items into a plan, keeping the summary up to date"]
+pub struct PlanBuilder<'a> {
+    plan: Plan,
+    vcs: VcsCheck<'a>,
+}
+
+impl<'a> PlanBuilder<'a> {
+    /// `fs` is what the plan is made against, where version control is
+    /// looked up
+    pub fn new(fs: &'a dyn FileSystem, command: CommandKind, working_dir: PathBuf) -> Self {
+        Self {
+            plan: Plan {
+                metadata: PlanMetadata {
+                    command,
+                    working_dir,
+                    created_at: SystemTime::now(),
+                },
+                actions: Vec::new(),
+                warnings: Vec::new(),
+                errors: Vec::new(),
+                summary: PlanSummary::default(),
+                patterns: Vec::new(),
+                skipped: Vec::new(),
+                omitted: 0,
+            },
+            vcs: VcsCheck::new(fs),
+        }
+    }
+
+    pub fn push(&mut self, item: PlanItem) {
+        if let Some(action) = self.record(item) {
+            self.plan.actions.push(action);
+        }
+    }
+
+    /// Accounts for `item` and hands back actions for the caller to keep
+    fn record(&mut self, item: PlanItem) -> Option<Action> {
+        let summary = &mut self.plan.summary;
+        match item {
+            PlanItem::Warning(warning) => self.plan.warnings.push(warning),
+            PlanItem::Error(error) => self.plan.errors.push(error),
+            PlanItem::Kept { is_dir: true } => summary.dirs_kept += 1,
+            PlanItem::Kept { is_dir: false } => summary.files_kept += 1,
+            PlanItem::Action(action) => {
+                match &action {
+                    Action::Create {
+                        kind: FsObjectKind::Directory,
+                        ..
+                    } => summary.dirs_created += 1,
+                    Action::Create { .. } => summary.files_created += 1,
+                    Action::Move { backup: true, .. } => summary.files_backed_up += 1,
+                    Action::Move { size, .. } => {
+                        summary.files_moved += 1;
+                        summary.bytes_moved += size;
+                    }
+                    Action::Copy { size, .. } => {
+                        summary.files_copied += 1;
+                        summary.bytes_copied += size;
+                    }
+                    Action::Link { .. } => summary.links_created += 1,
+                    Action::Modify { .. } => summary.files_modified += 1,
+                    Action::Delete {
+                        kind: FsObjectKind::Directory,
+                        ..
+                    } => summary.dirs_deleted += 1,
+                    Action::Delete { size, .. } => {
+                        summary.files_deleted += 1;
+                        summary.bytes_deleted += size;
+                    }
+                }
+                self.vcs.observe(&action);
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    pub fn finish(self) -> Plan {
+        let Self { mut plan, vcs } = self;
+        plan.warnings.extend(vcs.finish());
+        plan.summary.warnings = plan.warnings.len();
+        plan.summary.errors = plan.errors.len();
+        plan
+    }
+}
+
+#[doc = "A plan whose actions are spilled to a temporary file"]
+#[derive(Debug)]
+pub struct PlanSpool {
+    /// Summary, warnings and errors, with only the first actions as preview
+    pub plan: Plan,
+    pub actions_total: usize,
+    pub bytes_total: u64,
+    file: SpoolFile,
+}
+
+/// Temporary file removed when dropped
+#[derive(Debug)]
+struct SpoolFile {
+    path: PathBuf,
+}
+
+impl PlanSpool {
+    /// Drains `items`, keeping up to `preview` actions, and as many paths per
+    /// version control warning, in memory and writing all actions, one JSON
+    /// document per line, to a temporary file
+    pub fn collect(
+        mut builder: PlanBuilder<'_>,
+        items: impl Iterator<Item = PlanItem>,
+        preview: usize,
+    ) -> io::Result<Self> {
+        let path = env::temp_dir().join(format!(
+            "elvis-{}-{}.jsonl",
+            process::id(),
+            SPOOLS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut writer = BufWriter::new(File::options().write(true).create_new(true).open(&path)?);
+        // From here on the file is removed again, also when collecting fails
+        let file = SpoolFile { path };
+        builder.vcs = builder.vcs.with_limit(preview);
+
+        let mut actions_total = 0;
+        let mut bytes_total = 0;
+        for item in items {
+            let Some(action) = builder.record(item) else {
+                continue;
+            };
+
+            serde_json::to_writer(&mut writer, &action)?;
+            writer.write_all(b"\n")?;
+            actions_total += 1;
+            bytes_total += action.size();
+
+            if builder.plan.actions.len() < preview {
+                builder.plan.actions.push(action);
+            } else {
+                builder.plan.omitted += 1;
+            }
+        }
+        writer.flush()?;
+
+        Ok(Self {
+            plan: builder.finish(),
+            actions_total,
+            bytes_total,
+            file,
+        })
+    }
+
+    /// Reads the actions back in plan order
+    pub fn actions(&self) -> io::Result<impl Iterator<Item = io::Result<Action>> + use<>> {
+        let reader = BufReader::new(File::open(&self.file.path)?);
+        Ok(reader
+            .lines()
+            .map(|line| serde_json::from_str(&line?).map_err(io::Error::from)))
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
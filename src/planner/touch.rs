@@ -1,6 +1,9 @@
-use crate::planner::{
-    action::{Action, FsObjectKind},
-    plan::{CommandKind, Plan, PlanMetadata, PlanSummary, PlanWarning, WarningKind},
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary, PlanWarning, WarningKind},
+    },
+    vfs::traits::FileSystem,
 };
 use std::{path::PathBuf, time::SystemTime};
 
@@ -17,14 +20,15 @@ impl TouchPlanner {
 }
 
 impl super::traits::Planner for TouchPlanner {
-    fn plan(&self) -> Plan {
+    fn plan(&self, fs: &dyn FileSystem) -> Plan {
         let mut actions = Vec::new();
         let mut warnings = Vec::new();
         let errors = Vec::new();
         let mut summary = PlanSummary::default();
 
         for target in self.targets.iter() {
-            if target.exists() {
+            // Touch follows symlinks, so the entry is what they point at
+            if let Ok(metadata) = fs.metadata(target) {
                 warnings.push(PlanWarning {
                     kind: WarningKind::Overwrite,
                     paths: vec![target.clone()],
@@ -32,7 +36,9 @@ impl super::traits::Planner for TouchPlanner {
                 });
                 actions.push(Action::Modify {
                     path: target.clone(),
+                    kind: metadata.kind,
                     description: "Update modification time".into(),
+                    modified: metadata.modified,
                 });
                 summary.files_modified += 1;
             } else {
@@ -57,6 +63,9 @@ impl super::traits::Planner for TouchPlanner {
             warnings,
             errors,
             summary,
+            patterns: Vec::new(),
+            skipped: Vec::new(),
+            omitted: 0,
         }
     }
 }
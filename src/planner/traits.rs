@@ -1,6 +1,8 @@
-use crate::planner::plan::Plan;
+use crate::{planner::plan::Plan, vfs::traits::FileSystem};
 
 #[doc = "Trait for planner"]
 pub trait Planner {
-    fn plan(&self) -> Plan;
+    /// Plans the command against `fs`, which may be the real disk or a
+    /// simulated tree
+    fn plan(&self, fs: &dyn FileSystem) -> Plan;
 }
@@ -0,0 +1,188 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::{PlanWarning, WarningKind},
+    },
+    vfs::traits::FileSystem,
+};
+
+#[doc = "Files git knows about in one repository, relative to its root"]
+#[derive(Debug, Clone, Default)]
+pub struct RepoState {
+    /// Files in the index
+    pub tracked: HashSet<PathBuf>,
+    /// Files with uncommitted changes, including untracked ones
+    pub changed: HashSet<PathBuf>,
+}
+
+#[doc = "Warns when actions destroy files that are under version control"]
+pub struct VcsCheck<'a> {
+    fs: &'a dyn FileSystem,
+    repos: HashMap<PathBuf, Option<RepoState>>,
+    /// Paths kept per warning; the rest are only counted
+    limit: usize,
+    tracked: Vec<PathBuf>,
+    tracked_total: usize,
+    changed: Vec<PathBuf>,
+    changed_total: usize,
+}
+
+impl RepoState {
+    /// Parses the output of `git ls-files -z` and
+    /// `git status --porcelain -z -uall`
+    pub fn parse(ls_files: &str, status: &str) -> Self {
+        let mut state = Self {
+            tracked: ls_files
+                .split('\0')
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            changed: HashSet::new(),
+        };
+
+        // Entries are `XY path`; renames and copies carry the old path as an
+        // extra field which is skipped
+        let mut fields = status.split('\0');
+        while let Some(entry) = fields.next() {
+            if entry.len() < 4 {
+                continue;
+            }
+            let (code, path) = entry.split_at(3);
+            state.changed.insert(PathBuf::from(path));
+            if code.starts_with('R') || code.starts_with('C') {
+                fields.next();
+            }
+        }
+
+        state
+    }
+}
+
+impl<'a> VcsCheck<'a> {
+    /// Repositories are looked up and read through `fs`
+    pub fn new(fs: &'a dyn FileSystem) -> Self {
+        Self {
+            fs,
+            repos: HashMap::new(),
+            limit: usize::MAX,
+            tracked: Vec::new(),
+            tracked_total: 0,
+            changed: Vec::new(),
+            changed_total: 0,
+        }
+    }
+
+    /// Keeps at most `limit` paths in each warning, for plans that are too
+    /// large to list
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Collects warnings for deletes and overwrites that hit files tracked
+    /// by git or holding uncommitted changes.
+    pub fn warnings(fs: &'a dyn FileSystem, actions: &[Action]) -> Vec<PlanWarning> {
+        let mut check = Self::new(fs);
+        for action in actions {
+            check.observe(action);
+        }
+        check.finish()
+    }
+
+    /// Checks one action, for plans whose actions are not all in memory
+    pub fn observe(&mut self, action: &Action) {
+        let path = match action {
+            Action::Delete {
+                path,
+                kind: FsObjectKind::File | FsObjectKind::Symlink,
+                ..
+            } => path,
+            Action::Move {
+                to,
+                overwrite: Some(_),
+                ..
+            }
+            | Action::Copy {
+                to,
+                overwrite: Some(_),
+                ..
+            } => to,
+            _ => return,
+        };
+
+        let Some((root, state)) = self.state_for(path) else {
+            return;
+        };
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        let Ok(relative) = absolute.strip_prefix(&root) else {
+            return;
+        };
+
+        let changed = state.changed.contains(relative);
+        let tracked = state.tracked.contains(relative);
+
+        let (paths, total) = if changed {
+            (&mut self.changed, &mut self.changed_total)
+        } else if tracked {
+            (&mut self.tracked, &mut self.tracked_total)
+        } else {
+            return;
+        };
+        *total += 1;
+        if paths.len() < self.limit {
+            paths.push(path.clone());
+        }
+    }
+
+    /// Warnings for everything observed so far
+    pub fn finish(self) -> Vec<PlanWarning> {
+        let Self {
+            tracked,
+            tracked_total,
+            changed,
+            changed_total,
+            ..
+        } = self;
+
+        let mut warnings = Vec::new();
+        if changed_total > 0 {
+            warnings.push(PlanWarning {
+                kind: WarningKind::UncommittedChanges,
+                message: format!(
+                    "{} files with uncommitted changes will be lost",
+                    changed_total
+                ),
+                paths: changed,
+            });
+        }
+        if tracked_total > 0 {
+            warnings.push(PlanWarning {
+                kind: WarningKind::VcsTracked,
+                message: format!("{} files tracked by git will be removed", tracked_total),
+                paths: tracked,
+            });
+        }
+        warnings
+    }
+
+    fn state_for(&mut self, path: &Path) -> Option<(PathBuf, &RepoState)> {
+        let absolute = std::path::absolute(path).ok()?;
+        let root = absolute
+            .ancestors()
+            .skip(1)
+            .find(|dir| self.fs.exists(&dir.join(".git")))?
+            .to_path_buf();
+
+        let fs = self.fs;
+        let state = self
+            .repos
+            .entry(root.clone())
+            .or_insert_with(|| fs.repo_state(&root));
+        state.as_ref().map(|state| (root, state))
+    }
+}
@@ -0,0 +1,129 @@
+use console::{set_colors_enabled, style};
+
+use crate::{
+    executor::audit::{AuditRecord, Outcome},
+    planner::plan::PlanSummary,
+    printer::{
+        format::{human_size, timestamp},
+        options::PrinterOptions,
+        pretty::PrettyPrinter,
+    },
+};
+
+#[doc = "Prints runs recorded in the audit log"]
+#[derive(Debug)]
+pub struct HistoryPrinter;
+
+impl HistoryPrinter {
+    /// One line per run, numbered like the log; `runs` pairs each record
+    /// with its id
+    pub fn print_list(runs: &[(usize, &AuditRecord)], options: &PrinterOptions) {
+        set_colors_enabled(options.use_color);
+
+        if runs.is_empty() {
+            println!("No recorded runs");
+            return;
+        }
+
+        for (id, record) in runs {
+            println!(
+                "{:>5}  {}  {}  {}@{}  {}",
+                style(format!("#{}", id)).bold(),
+                timestamp(record.time),
+                Self::outcome(record.outcome),
+                record.user,
+                record.host,
+                shell_words::join(&record.command_line)
+            );
+            println!("       {}", Self::summary_line(&record.summary));
+        }
+    }
+
+    /// Re-renders the recorded plan, followed by what failed
+    pub fn print_record(id: usize, record: &AuditRecord, options: &PrinterOptions) {
+        set_colors_enabled(options.use_color);
+
+        println!("{}", style(format!("Run #{}", id)).bold());
+        println!("  Time: {}", timestamp(record.time));
+        println!("  User: {}@{}", record.user, record.host);
+        println!("  Directory: {}", record.cwd.display());
+        println!("  Command: {}", shell_words::join(&record.command_line));
+        println!(
+            "  Outcome: {} ({} applied, {} failed, {} skipped)",
+            Self::outcome(record.outcome),
+            record.applied.len(),
+            record.failed.len(),
+            record.skipped.len()
+        );
+        println!();
+
+        let options = PrinterOptions {
+            cwd: record.cwd.clone(),
+            ..options.clone()
+        };
+        PrettyPrinter::print(&record.plan(), &options);
+
+        if record.failed.is_empty() {
+            return;
+        }
+        println!("{}", style("Failed").red());
+        for failed in record.failed.iter() {
+            println!("  {}", failed.error);
+        }
+        println!();
+    }
+
+    fn outcome(outcome: Outcome) -> console::StyledObject<&'static str> {
+        match outcome {
+            Outcome::Succeeded => style("succeeded").green(),
+            Outcome::Failed => style("failed").red(),
+        }
+    }
+
+    fn summary_line(s: &PlanSummary) -> String {
+        let mut parts = Vec::new();
+        if s.files_deleted > 0 || s.dirs_deleted > 0 {
+            parts.push(format!(
+                "deleted {} files, {} directories ({})",
+                s.files_deleted,
+                s.dirs_deleted,
+                human_size(s.bytes_deleted)
+            ));
+        }
+        if s.files_created > 0 || s.dirs_created > 0 {
+            parts.push(format!(
+                "created {} files, {} directories",
+                s.files_created, s.dirs_created
+            ));
+        }
+        if s.files_moved > 0 {
+            parts.push(format!(
+                "moved {} files ({})",
+                s.files_moved,
+                human_size(s.bytes_moved)
+            ));
+        }
+        if s.files_backed_up > 0 {
+            parts.push(format!("backed up {} files", s.files_backed_up));
+        }
+        if s.files_copied > 0 {
+            parts.push(format!(
+                "copied {} files ({})",
+                s.files_copied,
+                human_size(s.bytes_copied)
+            ));
+        }
+        if s.links_created > 0 {
+            parts.push(format!("linked {} files", s.links_created));
+        }
+        if s.files_modified > 0 {
+            parts.push(format!("modified {} files", s.files_modified));
+        }
+
+        if parts.is_empty() {
+            "no changes".into()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
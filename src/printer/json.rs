@@ -0,0 +1,41 @@
+use std::io;
+
+use serde::Serialize;
+
+use crate::{
+    executor::simulation::Simulation,
+    planner::plan::Plan,
+    printer::{options::PrinterOptions, stats::PlanStats},
+};
+
+/// Document written by [`JsonPrinter`]
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    plan: &'a Plan,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<PlanStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<&'a Simulation>,
+}
+
+#[doc = "Prints a plan as a JSON document"]
+#[derive(Debug)]
+pub struct JsonPrinter;
+
+impl JsonPrinter {
+    pub fn print(
+        plan: &Plan,
+        simulation: Option<&Simulation>,
+        options: &PrinterOptions,
+    ) -> io::Result<()> {
+        let output = JsonOutput {
+            plan,
+            stats: options.stats.then(|| PlanStats::from_plan(plan)),
+            simulation,
+        };
+
+        serde_json::to_writer_pretty(io::stdout().lock(), &output)?;
+        println!();
+        Ok(())
+    }
+}
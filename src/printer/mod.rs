@@ -1,2 +1,10 @@
+pub mod format;
+pub mod history;
+pub mod json;
 pub mod options;
 pub mod pretty;
+pub mod progress;
+pub mod report;
+pub mod simulation;
+pub mod stats;
+pub mod tree;
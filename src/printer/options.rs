@@ -1,11 +1,26 @@
 use std::path::PathBuf;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PrinterOptions {
     pub summary_only: bool,
     pub max_entries: usize,
     pub cwd: PathBuf,
     pub use_color: bool,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub tree: bool,
+    pub stats: bool,
+}
+
+#[doc = "Order of entries within an action section"]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Keep the order in which the planner produced the actions
+    #[default]
+    Plan,
+    Path,
+    Size,
+    Mtime,
 }
 
 impl Default for PrinterOptions {
@@ -15,6 +30,10 @@ impl Default for PrinterOptions {
             max_entries: 50,
             cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             use_color: true,
+            sort: SortKey::default(),
+            reverse: false,
+            tree: false,
+            stats: false,
         }
     }
 }
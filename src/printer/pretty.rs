@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::Path};
+use std::{path::Path, time::SystemTime};
 
 use console::{set_colors_enabled, style};
 
@@ -7,9 +7,17 @@ use crate::{
         action::{Action, FsObjectKind},
         plan::Plan,
     },
-    printer::options::PrinterOptions,
+    printer::{
+        format::{human_age, human_size},
+        options::{PrinterOptions, SortKey},
+        stats::{FileStat, PlanStats},
+        tree::TreePrinter,
+    },
 };
 
+/// Action sections in the order they are printed
+const SECTIONS: [&str; 6] = ["Create", "Copy", "Link", "Modify", "Move", "Delete"];
+
 #[derive(Debug)]
 pub struct PrettyPrinter;
 
@@ -25,7 +33,23 @@ impl PrettyPrinter {
         }
 
         Self::print_warnings(plan);
-        Self::print_actions(plan, options);
+        Self::print_patterns(plan, options);
+
+        if options.stats {
+            Self::print_stats(plan, options);
+        }
+
+        if options.tree {
+            TreePrinter::print(plan, options);
+        } else {
+            Self::print_actions(plan, options);
+        }
+        Self::print_skipped(plan, options);
+
+        if plan.omitted > 0 {
+            println!("... ({} more actions not previewed)", plan.omitted);
+            println!();
+        }
     }
 
     fn print_errors(plan: &Plan) {
@@ -55,23 +79,89 @@ impl PrettyPrinter {
         println!();
     }
 
+    fn print_patterns(plan: &Plan, options: &PrinterOptions) {
+        if plan.patterns.is_empty() {
+            return;
+        }
+
+        println!("Patterns:");
+        for pattern in plan.patterns.iter() {
+            let total = pattern.matches.len();
+            match total {
+                0 => println!("  {} (no matches)", pattern.pattern),
+                1 => println!("  {} (1 match)", pattern.pattern),
+                _ => println!("  {} ({} matches)", pattern.pattern, total),
+            }
+
+            let limit = options.max_entries.min(total);
+            for path in pattern.matches.iter().take(limit) {
+                println!("    {}", Self::rel_path(path, options));
+            }
+
+            if total > limit {
+                println!("    ... ({} more)", total - limit);
+            }
+        }
+        println!();
+    }
+
     fn print_summary(plan: &Plan) {
         let s = &plan.summary;
 
         println!("Plan summary:");
         if s.files_deleted > 0 || s.dirs_deleted > 0 {
             println!(
-                "  Delete: {} files, {} directories",
-                s.files_deleted, s.dirs_deleted
+                "  Delete: {} files, {} directories ({})",
+                s.files_deleted,
+                s.dirs_deleted,
+                human_size(s.bytes_deleted)
             );
         }
 
-        if s.files_created > 0 {
-            println!("  Create: {} files", s.files_created);
+        if s.files_created > 0 || s.dirs_created > 0 {
+            println!(
+                "  Create: {} files, {} directories",
+                s.files_created, s.dirs_created
+            );
         }
 
         if s.files_moved > 0 {
-            println!("  Move: {} files", s.files_moved);
+            println!(
+                "  Move: {} files ({})",
+                s.files_moved,
+                human_size(s.bytes_moved)
+            );
+        }
+
+        if s.files_backed_up > 0 {
+            println!("  Backup: {} files", s.files_backed_up);
+        }
+
+        if s.files_kept > 0 || s.dirs_kept > 0 {
+            println!(
+                "  Keep: {} files, {} directories (filtered)",
+                s.files_kept, s.dirs_kept
+            );
+        }
+
+        if s.files_skipped > 0 {
+            println!("  Skip: {} files (destination kept)", s.files_skipped);
+        }
+
+        if s.files_copied > 0 {
+            println!(
+                "  Copy: {} files ({})",
+                s.files_copied,
+                human_size(s.bytes_copied)
+            );
+        }
+
+        if s.links_created > 0 {
+            println!("  Link: {} links", s.links_created);
+        }
+
+        if s.bytes_overwritten > 0 {
+            println!("  Overwrite: {}", human_size(s.bytes_overwritten));
         }
 
         println!("Warnings: {}", s.warnings);
@@ -79,27 +169,74 @@ impl PrettyPrinter {
         println!();
     }
 
-    fn print_actions(plan: &Plan, options: &PrinterOptions) {
-        let mut groups = HashMap::<&'static str, Vec<&Action>>::new();
+    fn print_stats(plan: &Plan, options: &PrinterOptions) {
+        let stats = PlanStats::from_plan(plan);
+        if stats.largest.is_empty() {
+            return;
+        }
 
-        for action in &plan.actions {
-            let key = match action {
-                Action::Create { .. } => "Create",
-                Action::Modify { .. } => "Modify",
-                Action::Move { .. } => "Move",
-                Action::Delete { .. } => "Delete",
-            };
+        println!("Stats:");
 
-            groups.entry(key).or_default().push(action);
+        println!("  Largest:");
+        for file in stats.largest.iter() {
+            println!(
+                "    {:>10}  {}",
+                human_size(file.size),
+                Self::rel_path(&file.path, options)
+            );
         }
 
-        for (group, action) in groups {
-            println!("{}:", group);
+        if let Some(oldest) = &stats.oldest {
+            Self::print_age("Oldest", oldest, options);
+        }
+        if let Some(newest) = &stats.newest {
+            Self::print_age("Newest", newest, options);
+        }
+
+        println!("  By extension:");
+        for (extension, stat) in stats.by_extension.iter() {
+            println!(
+                "    {:<10} {} files, {}",
+                extension,
+                stat.files,
+                human_size(stat.bytes)
+            );
+        }
 
-            let total = action.len();
+        println!();
+    }
+
+    fn print_age(label: &str, file: &FileStat, options: &PrinterOptions) {
+        if let Some(mtime) = file.mtime {
+            println!(
+                "  {}: {} ({})",
+                label,
+                Self::rel_path(&file.path, options),
+                human_age(mtime)
+            );
+        }
+    }
+
+    fn print_actions(plan: &Plan, options: &PrinterOptions) {
+        for section in SECTIONS {
+            let mut actions: Vec<&Action> = plan
+                .actions
+                .iter()
+                .filter(|action| Self::section(action) == section)
+                .collect();
+
+            if actions.is_empty() {
+                continue;
+            }
+
+            Self::sort_actions(&mut actions, options);
+
+            println!("{}:", section);
+
+            let total = actions.len();
             let limit = options.max_entries.min(total);
 
-            for action in action.iter().take(limit) {
+            for action in actions.iter().take(limit) {
                 Self::print_action(action, options);
             }
 
@@ -111,6 +248,72 @@ impl PrettyPrinter {
         }
     }
 
+    fn print_skipped(plan: &Plan, options: &PrinterOptions) {
+        if plan.skipped.is_empty() {
+            return;
+        }
+
+        println!("Skip:");
+        let limit = options.max_entries.min(plan.skipped.len());
+        for entry in plan.skipped.iter().take(limit) {
+            println!(
+                "{}  {} -> {} ({})",
+                style("S").dim(),
+                Self::rel_path(&entry.path, options),
+                Self::rel_path(&entry.destination, options),
+                entry.reason
+            );
+        }
+        if plan.skipped.len() > limit {
+            println!("  ... ({} more)", plan.skipped.len() - limit);
+        }
+        println!();
+    }
+
+    fn section(action: &Action) -> &'static str {
+        match action {
+            Action::Create { .. } => "Create",
+            Action::Copy { .. } => "Copy",
+            Action::Link { .. } => "Link",
+            Action::Modify { .. } => "Modify",
+            Action::Move { .. } => "Move",
+            Action::Delete { .. } => "Delete",
+        }
+    }
+
+    fn sort_actions(actions: &mut [&Action], options: &PrinterOptions) {
+        match options.sort {
+            SortKey::Plan => {}
+            SortKey::Path => actions.sort_by(|a, b| Self::subject(a).cmp(Self::subject(b))),
+            SortKey::Size => actions.sort_by(|a, b| {
+                a.size()
+                    .cmp(&b.size())
+                    .then_with(|| Self::subject(a).cmp(Self::subject(b)))
+            }),
+            SortKey::Mtime => actions.sort_by(|a, b| {
+                let mtime = |action: &Action| action.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                mtime(a)
+                    .cmp(&mtime(b))
+                    .then_with(|| Self::subject(a).cmp(Self::subject(b)))
+            }),
+        }
+
+        if options.reverse {
+            actions.reverse();
+        }
+    }
+
+    /// The path an action is sorted by: the source for moves and copies
+    fn subject(action: &Action) -> &Path {
+        match action {
+            Action::Create { path, .. }
+            | Action::Link { path, .. }
+            | Action::Modify { path, .. }
+            | Action::Delete { path, .. } => path,
+            Action::Move { from, .. } | Action::Copy { from, .. } => from,
+        }
+    }
+
     fn print_action(action: &Action, options: &PrinterOptions) {
         match action {
             Action::Create { path, kind, .. } => {
@@ -121,7 +324,26 @@ impl PrettyPrinter {
                     Self::kind_suffix(*kind)
                 )
             }
-            Action::Modify { path, description } => {
+            Action::Copy { from, to, size, .. } => {
+                println!(
+                    "{}  {} -> {} ({})",
+                    style("C").green(),
+                    Self::rel_path(from, options),
+                    Self::rel_path(to, options),
+                    human_size(*size)
+                )
+            }
+            Action::Link { path, target, .. } => {
+                println!(
+                    "{}  {} -> {}",
+                    style("L").cyan(),
+                    Self::rel_path(path, options),
+                    target.display()
+                )
+            }
+            Action::Modify {
+                path, description, ..
+            } => {
                 println!(
                     "{}  {} ({})",
                     style("M").yellow(),
@@ -129,26 +351,39 @@ impl PrettyPrinter {
                     description
                 )
             }
-            Action::Move { from, to, .. } => {
+            Action::Move { from, to, size, .. } => {
                 println!(
-                    "{}  {} -> {}",
+                    "{}  {} -> {} ({})",
                     style("M").yellow(),
                     Self::rel_path(from, options),
-                    Self::rel_path(to, options)
+                    Self::rel_path(to, options),
+                    human_size(*size)
                 )
             }
-            Action::Delete { path, kind, .. } => {
+            Action::Delete {
+                path,
+                kind: FsObjectKind::Directory,
+                ..
+            } => {
                 println!(
                     "{}  {}{}",
                     style("D").red(),
                     Self::rel_path(path, options),
-                    Self::kind_suffix(*kind)
+                    Self::kind_suffix(FsObjectKind::Directory)
+                )
+            }
+            Action::Delete { path, size, .. } => {
+                println!(
+                    "{}  {} ({})",
+                    style("D").red(),
+                    Self::rel_path(path, options),
+                    human_size(*size)
                 )
             }
         }
     }
 
-    fn rel_path(path: &Path, options: &PrinterOptions) -> String {
+    pub(crate) fn rel_path(path: &Path, options: &PrinterOptions) -> String {
         path.strip_prefix(&options.cwd)
             .unwrap_or(path)
             .display()
@@ -0,0 +1,104 @@
+use std::{
+    io::{self, IsTerminal},
+    time::{Duration, Instant},
+};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::{
+    executor::progress::{Progress, ProgressEvent, ProgressReporter},
+    printer::format::{human_duration, human_size},
+};
+
+/// Time between two log lines when stderr is not a terminal
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+#[doc = "Shows execution progress on stderr: a bar on a terminal, log lines otherwise"]
+#[derive(Debug)]
+pub enum ProgressPrinter {
+    Bar(ProgressBar),
+    Log {
+        last: Instant,
+        /// Whether any line was printed, so short runs stay quiet
+        printed: bool,
+    },
+    Hidden,
+}
+
+impl ProgressPrinter {
+    pub fn new(enabled: bool) -> Self {
+        if !enabled {
+            Self::Hidden
+        } else if io::stderr().is_terminal() {
+            let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stderr());
+            bar.set_style(
+                ProgressStyle::with_template("{bar:30.cyan/blue} {pos}/{len} actions, {msg}")
+                    .expect("valid progress template")
+                    .progress_chars("=> "),
+            );
+            Self::Bar(bar)
+        } else {
+            Self::Log {
+                last: Instant::now(),
+                printed: false,
+            }
+        }
+    }
+
+    /// Bytes, rate and time left, e.g. `1.0 MiB / 4.0 MiB, 512.0 KiB/s, ETA 6s`
+    fn describe(progress: &Progress) -> String {
+        let eta = progress
+            .eta()
+            .map_or_else(|| "-".to_string(), human_duration);
+        format!(
+            "{} / {}, {}/s, ETA {}",
+            human_size(progress.bytes_done),
+            human_size(progress.bytes_total),
+            human_size(progress.bytes_per_sec() as u64),
+            eta
+        )
+    }
+}
+
+impl ProgressReporter for ProgressPrinter {
+    fn report(&mut self, event: ProgressEvent) {
+        match self {
+            Self::Hidden => {}
+            Self::Bar(bar) => match event {
+                ProgressEvent::Started(progress) => {
+                    bar.set_length(progress.actions_total as u64);
+                    bar.set_message(Self::describe(&progress));
+                }
+                ProgressEvent::Advanced(progress) => {
+                    bar.set_position(progress.actions_done as u64);
+                    bar.set_message(Self::describe(&progress));
+                }
+                ProgressEvent::Finished(_) => bar.finish_and_clear(),
+            },
+            Self::Log { last, printed } => match event {
+                ProgressEvent::Started(_) => *last = Instant::now(),
+                ProgressEvent::Advanced(progress) => {
+                    if last.elapsed() >= LOG_INTERVAL {
+                        eprintln!(
+                            "elvis: {}/{} actions, {}",
+                            progress.actions_done,
+                            progress.actions_total,
+                            Self::describe(&progress)
+                        );
+                        *last = Instant::now();
+                        *printed = true;
+                    }
+                }
+                ProgressEvent::Finished(progress) => {
+                    if *printed {
+                        eprintln!(
+                            "elvis: done, {} actions in {}",
+                            progress.actions_done,
+                            human_duration(progress.elapsed)
+                        );
+                    }
+                }
+            },
+        }
+    }
+}
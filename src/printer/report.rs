@@ -0,0 +1,88 @@
+use std::fmt;
+
+use console::{set_colors_enabled, style};
+
+use crate::{
+    error::Error,
+    executor::report::ExecutionReport,
+    planner::{action::Action, plan::Plan},
+    printer::{options::PrinterOptions, pretty::PrettyPrinter},
+};
+
+#[doc = "Prints which actions of an executed plan failed or were skipped"]
+#[derive(Debug)]
+pub struct ReportPrinter;
+
+impl ReportPrinter {
+    pub fn print(report: &ExecutionReport, plan: &Plan, options: &PrinterOptions) {
+        set_colors_enabled(options.use_color);
+
+        println!("{}", style("Execution").bold());
+        println!(
+            "  {} succeeded, {} failed, {} skipped",
+            report.succeeded.len(),
+            report.failed.len(),
+            report.skipped.len()
+        );
+
+        if options.summary_only {
+            return;
+        }
+
+        for failed in report.failed.iter() {
+            let label = if failed.drifted() {
+                "changed"
+            } else {
+                "failed"
+            };
+            // The action is described already, so only show what went wrong
+            let error: &dyn fmt::Display = match &failed.error {
+                Error::ActionFailed { source, .. } => source,
+                other => other,
+            };
+            println!(
+                "  {} action {}: {} - {}",
+                style(label).red(),
+                failed.index + 1,
+                Self::describe(plan, failed.index, options),
+                error
+            );
+        }
+
+        let limit = options.max_entries;
+        for skipped in report.skipped.iter().take(limit) {
+            println!(
+                "  {} action {}: {} (after action {})",
+                style("skipped").yellow(),
+                skipped.index + 1,
+                Self::describe(plan, skipped.index, options),
+                skipped.cause + 1
+            );
+        }
+        if report.skipped.len() > limit {
+            println!("  ... ({} more skipped)", report.skipped.len() - limit);
+        }
+        println!();
+    }
+
+    fn describe(plan: &Plan, index: usize, options: &PrinterOptions) -> String {
+        // Streamed plans only keep a preview of their actions in memory
+        let Some(action) = plan.actions.get(index) else {
+            return "(not in preview)".into();
+        };
+
+        let path = |p| PrettyPrinter::rel_path(p, options);
+        match action {
+            Action::Create { path: p, .. } => format!("create {}", path(p)),
+            Action::Copy { from, to, .. } => format!("copy {} -> {}", path(from), path(to)),
+            Action::Link {
+                path: p, target, ..
+            } => {
+                format!("link {} -> {}", path(p), target.display())
+            }
+            Action::Modify { path: p, .. } => format!("modify {}", path(p)),
+            Action::Move { from, to, .. } => format!("move {} -> {}", path(from), path(to)),
+            Action::Delete { path: p, .. } => format!("delete {}", path(p)),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use console::{set_colors_enabled, style};
+
+use crate::{
+    executor::simulation::Simulation,
+    planner::action::FsObjectKind,
+    printer::{format::human_size, options::PrinterOptions, pretty::PrettyPrinter},
+};
+
+#[doc = "Prints the outcome of a simulated execution"]
+#[derive(Debug)]
+pub struct SimulationPrinter;
+
+impl SimulationPrinter {
+    pub fn print(simulation: &Simulation, actions: usize, options: &PrinterOptions) {
+        set_colors_enabled(options.use_color);
+
+        println!("{}", style("Simulation").bold());
+        if simulation.is_ok() {
+            println!("  All {} actions would succeed", actions);
+        } else {
+            println!(
+                "  {}",
+                style(format!(
+                    "{} of {} actions would fail",
+                    simulation.failures.len(),
+                    actions
+                ))
+                .red()
+            );
+            for failure in simulation.failures.iter() {
+                println!(
+                    "  - action {}: {} - {}",
+                    failure.index + 1,
+                    PrettyPrinter::rel_path(&failure.path, options),
+                    failure.message
+                );
+            }
+        }
+        println!();
+
+        if options.summary_only || simulation.state.is_empty() {
+            return;
+        }
+
+        println!("{}", style("Predicted state").bold());
+        let limit = options.max_entries;
+        for entry in simulation.state.iter().take(limit) {
+            let path = PrettyPrinter::rel_path(&entry.path, options);
+            match entry.kind {
+                Some(FsObjectKind::Directory) => {
+                    println!("  {} {}/", style("+").green(), path)
+                }
+                Some(_) => println!(
+                    "  {} {} ({})",
+                    style("+").green(),
+                    path,
+                    human_size(entry.size)
+                ),
+                None => println!("  {} {}", style("-").red(), path),
+            }
+        }
+        if simulation.state.len() > limit {
+            println!("  ... ({} more)", simulation.state.len() - limit);
+        }
+        println!();
+    }
+}
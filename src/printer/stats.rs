@@ -0,0 +1,98 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::Serialize;
+
+use crate::planner::{
+    action::{Action, FsObjectKind},
+    plan::Plan,
+};
+
+/// Number of entries kept in [`PlanStats::largest`]
+const LARGEST_LIMIT: usize = 10;
+
+#[doc = "Statistics over the existing files a plan touches"]
+#[derive(Debug, Default, Serialize)]
+pub struct PlanStats {
+    pub largest: Vec<FileStat>,
+    pub oldest: Option<FileStat>,
+    pub newest: Option<FileStat>,
+    pub by_extension: BTreeMap<String, ExtensionStat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStat {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch
+    pub mtime: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ExtensionStat {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl PlanStats {
+    pub fn from_plan(plan: &Plan) -> Self {
+        let mut stats = Self::default();
+        let mut files = Vec::new();
+
+        for action in plan.actions.iter() {
+            // Backups are destinations set aside, not files the plan is about
+            let path = match action {
+                Action::Delete {
+                    kind: FsObjectKind::Directory,
+                    ..
+                }
+                | Action::Move { backup: true, .. }
+                | Action::Create { .. }
+                | Action::Link { .. } => continue,
+                Action::Delete { path, .. } | Action::Modify { path, .. } => path,
+                Action::Move { from, .. } | Action::Copy { from, .. } => from,
+            };
+
+            let file = FileStat {
+                path: path.clone(),
+                size: action.size(),
+                mtime: action
+                    .modified()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs()),
+            };
+
+            let extension = stats.by_extension.entry(Self::extension(path)).or_default();
+            extension.files += 1;
+            extension.bytes += file.size;
+
+            files.push(file);
+        }
+
+        stats.oldest = files
+            .iter()
+            .filter(|f| f.mtime.is_some())
+            .min_by_key(|f| f.mtime)
+            .cloned();
+        stats.newest = files
+            .iter()
+            .filter(|f| f.mtime.is_some())
+            .max_by_key(|f| f.mtime)
+            .cloned();
+
+        files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        files.truncate(LARGEST_LIMIT);
+        stats.largest = files;
+
+        stats
+    }
+
+    fn extension(path: &Path) -> String {
+        path.extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_else(|| "(none)".into())
+    }
+}
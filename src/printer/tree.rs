@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use console::{StyledObject, style};
+
+use crate::{
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::Plan,
+    },
+    printer::{format::human_size, options::PrinterOptions, pretty::PrettyPrinter},
+};
+
+/// One path component of the plan tree
+#[derive(Debug, Default)]
+struct Node {
+    path: PathBuf,
+    marker: Option<&'static str>,
+    is_dir: bool,
+    size: u64,
+    target: Option<PathBuf>,
+    children: BTreeMap<OsString, Node>,
+}
+
+/// Totals of a collapsed subtree
+#[derive(Debug, Default)]
+struct Totals {
+    files: usize,
+    dirs: usize,
+    bytes: u64,
+}
+
+#[doc = "Renders a plan as a directory tree"]
+#[derive(Debug)]
+pub struct TreePrinter;
+
+impl TreePrinter {
+    pub fn print(plan: &Plan, options: &PrinterOptions) {
+        let mut root = Node::default();
+        for action in plan.actions.iter() {
+            Self::insert(&mut root, action, options);
+        }
+
+        let mut lines = Vec::new();
+        for child in root.children.values() {
+            Self::render(child, PathBuf::new(), 0, options, &mut lines);
+        }
+
+        println!("Tree:");
+
+        let total = lines.len();
+        let limit = options.max_entries.min(total);
+        for line in lines.iter().take(limit) {
+            println!("{}", line);
+        }
+
+        if total > limit {
+            println!("  ... ({} more)", total - limit);
+        }
+
+        println!();
+    }
+
+    fn insert(root: &mut Node, action: &Action, options: &PrinterOptions) {
+        let (path, marker, is_dir, target) = match action {
+            Action::Create { path, kind } => (path, "C", Self::is_dir(*kind), None),
+            Action::Copy { to, .. } => (to, "C", false, None),
+            Action::Link { path, target, .. } => (path, "L", false, Some(target.clone())),
+            Action::Modify { path, kind, .. } => (path, "M", Self::is_dir(*kind), None),
+            Action::Move { from, to, kind, .. } => {
+                (from, "M", Self::is_dir(*kind), Some(to.clone()))
+            }
+            Action::Delete { path, kind, .. } => (path, "D", Self::is_dir(*kind), None),
+        };
+
+        // Nodes are named relative to the working directory
+        let (mut current, relative) = match path.strip_prefix(&options.cwd) {
+            Ok(relative) => (options.cwd.clone(), relative),
+            Err(_) => (PathBuf::new(), path.as_path()),
+        };
+
+        let mut node = root;
+        for component in relative.components() {
+            current.push(component);
+            node = node
+                .children
+                .entry(component.as_os_str().to_os_string())
+                .or_insert_with(|| Node {
+                    path: current.clone(),
+                    is_dir: true,
+                    ..Node::default()
+                });
+        }
+
+        if node.marker.is_none() {
+            node.marker = Some(marker);
+        }
+        node.is_dir = is_dir || !node.children.is_empty();
+        node.target = target;
+        node.size = action.size();
+    }
+
+    fn render(
+        node: &Node,
+        prefix: PathBuf,
+        depth: usize,
+        options: &PrinterOptions,
+        lines: &mut Vec<String>,
+    ) {
+        let name = prefix.join(node.path.file_name().unwrap_or(node.path.as_os_str()));
+
+        // Chains of untouched directories are joined into one line
+        if node.marker.is_none() && node.children.len() == 1 {
+            let child = node.children.values().next().unwrap();
+            if child.is_dir {
+                Self::render(child, name, depth, options, lines);
+                return;
+            }
+        }
+
+        let label = Self::label(&name, depth, node);
+        let marker = node.marker.map(Self::styled_marker);
+
+        if node.is_dir && Self::fully_affected(node) {
+            let mut totals = Totals::default();
+            Self::totals(node, &mut totals);
+            lines.push(format!(
+                "{}  {} ({} files, {} dirs, {})",
+                Self::marker_column(marker),
+                label,
+                totals.files,
+                totals.dirs,
+                human_size(totals.bytes)
+            ));
+            return;
+        }
+
+        let line = match &node.target {
+            Some(to) => format!(
+                "{}  {} -> {}",
+                Self::marker_column(marker),
+                label,
+                PrettyPrinter::rel_path(to, options)
+            ),
+            None => format!("{}  {}", Self::marker_column(marker), label),
+        };
+        lines.push(line);
+
+        for child in node.children.values() {
+            Self::render(child, PathBuf::new(), depth + 1, options, lines);
+        }
+    }
+
+    /// A directory is fully affected when it is created or deleted and so is
+    /// everything inside it. Nothing else can be inside: a new directory
+    /// only holds what the plan puts there, and a directory is only deleted
+    /// once the plan has emptied it. Entries moved out or modified keep
+    /// their directory expanded, so they stay listed.
+    fn fully_affected(node: &Node) -> bool {
+        matches!(node.marker, Some("C" | "D")) && node.children.values().all(Self::fully_affected)
+    }
+
+    fn totals(node: &Node, totals: &mut Totals) {
+        if node.is_dir {
+            totals.dirs += 1;
+        } else {
+            totals.files += 1;
+            totals.bytes += node.size;
+        }
+
+        for child in node.children.values() {
+            Self::totals(child, totals);
+        }
+    }
+
+    fn label(name: &Path, depth: usize, node: &Node) -> String {
+        let suffix = if node.is_dir { "/" } else { "" };
+        format!("{}{}{}", "  ".repeat(depth), name.display(), suffix)
+    }
+
+    fn marker_column(marker: Option<StyledObject<&'static str>>) -> String {
+        match marker {
+            Some(marker) => marker.to_string(),
+            None => " ".into(),
+        }
+    }
+
+    fn styled_marker(marker: &'static str) -> StyledObject<&'static str> {
+        match marker {
+            "C" => style(marker).green(),
+            "D" => style(marker).red(),
+            "L" => style(marker).cyan(),
+            _ => style(marker).yellow(),
+        }
+    }
+
+    fn is_dir(kind: FsObjectKind) -> bool {
+        matches!(kind, FsObjectKind::Directory)
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    planner::{action::FsObjectKind, vcs::RepoState},
+    vfs::traits::{FileSystem, Metadata},
+};
+
+/// How many symlinks are followed before giving up, like `ELOOP`
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+#[derive(Debug, Clone)]
+struct Node {
+    metadata: Metadata,
+    target: Option<PathBuf>,
+    /// Known only for files added with their text
+    contents: Option<String>,
+}
+
+#[doc = "A file system tree held in memory, for tests and simulated plans"]
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFs {
+    nodes: BTreeMap<PathBuf, Node>,
+    /// What git reports for each repository root
+    repos: BTreeMap<PathBuf, RepoState>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directory and any missing parents
+    pub fn add_dir(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        let path = path.into();
+        self.add_parents(&path);
+        self.nodes.insert(
+            path,
+            Node {
+                metadata: Self::metadata(FsObjectKind::Directory, 0),
+                target: None,
+                contents: None,
+            },
+        );
+        self
+    }
+
+    /// Adds a file of `len` bytes and any missing parents
+    pub fn add_file(&mut self, path: impl Into<PathBuf>, len: u64) -> &mut Self {
+        let path = path.into();
+        self.add_parents(&path);
+        self.nodes.insert(
+            path,
+            Node {
+                metadata: Self::metadata(FsObjectKind::File, len),
+                target: None,
+                contents: None,
+            },
+        );
+        self
+    }
+
+    /// Adds a file holding `text` and any missing parents
+    pub fn add_text_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        text: impl Into<String>,
+    ) -> &mut Self {
+        let path = path.into();
+        let text = text.into();
+        self.add_parents(&path);
+        self.nodes.insert(
+            path,
+            Node {
+                metadata: Self::metadata(FsObjectKind::File, text.len() as u64),
+                target: None,
+                contents: Some(text),
+            },
+        );
+        self
+    }
+
+    /// Adds a symlink pointing at `target`, resolved against the link's
+    /// directory when relative
+    pub fn add_symlink(
+        &mut self,
+        path: impl Into<PathBuf>,
+        target: impl Into<PathBuf>,
+    ) -> &mut Self {
+        let path = path.into();
+        self.add_parents(&path);
+        self.nodes.insert(
+            path,
+            Node {
+                metadata: Self::metadata(FsObjectKind::Symlink, 0),
+                target: Some(target.into()),
+                contents: None,
+            },
+        );
+        self
+    }
+
+    /// Sets the modification time of an existing entry
+    pub fn set_modified(&mut self, path: &Path, modified: SystemTime) -> &mut Self {
+        if let Some(node) = self.nodes.get_mut(path) {
+            node.metadata.modified = Some(modified);
+        }
+        self
+    }
+
+    /// Makes `root` a git repository, with its `.git` directory, that
+    /// reports `state`
+    pub fn add_repo(&mut self, root: impl Into<PathBuf>, state: RepoState) -> &mut Self {
+        let root = root.into();
+        self.add_dir(root.join(".git"));
+        self.repos.insert(root, state);
+        self
+    }
+
+    /// The node `path` points at, following symlinks
+    fn resolve(&self, path: &Path) -> io::Result<&Node> {
+        let mut current = path.to_path_buf();
+        for _ in 0..MAX_SYMLINK_DEPTH {
+            let node = self
+                .nodes
+                .get(&current)
+                .ok_or_else(|| Self::not_found(path))?;
+            match &node.target {
+                Some(target) => {
+                    current = match current.parent() {
+                        Some(parent) => parent.join(target),
+                        None => target.clone(),
+                    }
+                }
+                None => return Ok(node),
+            }
+        }
+
+        Err(io::Error::other(format!(
+            "{}: Too many levels of symbolic links",
+            path.display()
+        )))
+    }
+
+    fn add_parents(&mut self, path: &Path) {
+        for parent in path.ancestors().skip(1) {
+            if parent.as_os_str().is_empty() || self.nodes.contains_key(parent) {
+                continue;
+            }
+            self.nodes.insert(
+                parent.to_path_buf(),
+                Node {
+                    metadata: Self::metadata(FsObjectKind::Directory, 0),
+                    target: None,
+                    contents: None,
+                },
+            );
+        }
+    }
+
+    fn metadata(kind: FsObjectKind, len: u64) -> Metadata {
+        Metadata {
+            kind,
+            len,
+            modified: None,
+        }
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{}: No such file or directory", path.display()),
+        )
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.nodes
+            .get(path)
+            .map(|node| node.metadata)
+            .ok_or_else(|| Self::not_found(path))
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.resolve(path).map(|node| node.metadata)
+    }
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        if !self.symlink_metadata(dir)?.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("{}: Not a directory", dir.display()),
+            ));
+        }
+
+        Ok(self
+            .nodes
+            .range(dir.to_path_buf()..)
+            .skip(1)
+            .take_while(|(path, _)| path.starts_with(dir))
+            .filter(|(path, _)| path.parent() == Some(dir))
+            .map(|(path, _)| path.clone())
+            .collect())
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let node = self.resolve(path)?;
+        if node.metadata.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                format!("{}: Is a directory", path.display()),
+            ));
+        }
+        node.contents.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{}: Contents not known", path.display()),
+            )
+        })
+    }
+
+    fn repo_state(&self, root: &Path) -> Option<RepoState> {
+        self.repos.get(root).cloned()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod memory;
+pub mod real;
+pub mod traits;
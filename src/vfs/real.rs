@@ -0,0 +1,185 @@
+use std::{
+    cmp::Ordering,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+    thread,
+};
+
+use ignore::{WalkBuilder, WalkState};
+use walkdir::WalkDir;
+
+use crate::{
+    planner::{action::FsObjectKind, vcs::RepoState},
+    vfs::traits::{FileSystem, Metadata, WalkEntry},
+};
+
+#[doc = "The file system of the host"]
+#[derive(Debug, Clone, Copy)]
+pub struct RealFs {
+    /// Threads used by [`FileSystem::walk`]; one walks sequentially
+    pub threads: usize,
+}
+
+impl Default for RealFs {
+    fn default() -> Self {
+        Self { threads: 1 }
+    }
+}
+
+impl RealFs {
+    /// Walks directories with one thread per available CPU
+    pub fn parallel() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    fn convert(metadata: fs::Metadata) -> Metadata {
+        let kind = if metadata.is_dir() {
+            FsObjectKind::Directory
+        } else if metadata.file_type().is_symlink() {
+            FsObjectKind::Symlink
+        } else {
+            FsObjectKind::File
+        };
+
+        Metadata {
+            kind,
+            len: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        }
+    }
+
+    fn walk_sequential(
+        root: &Path,
+        contents_first: bool,
+    ) -> impl Iterator<Item = WalkEntry> + use<> {
+        WalkDir::new(root)
+            .sort_by_file_name()
+            .contents_first(contents_first)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some(WalkEntry {
+                    path: entry.into_path(),
+                    metadata: Self::convert(metadata),
+                })
+            })
+    }
+
+    /// Reads directories on several threads, then sorts the entries into
+    /// the order [`Self::walk_sequential`] yields them in
+    fn walk_parallel(&self, root: &Path, contents_first: bool) -> Vec<WalkEntry> {
+        let entries = Mutex::new(Vec::new());
+
+        WalkBuilder::new(root)
+            .standard_filters(false)
+            .hidden(false)
+            .follow_links(false)
+            .threads(self.threads)
+            .build_parallel()
+            .run(|| {
+                Box::new(|entry| {
+                    if let Ok(entry) = entry
+                        && let Ok(metadata) = entry.metadata()
+                    {
+                        entries.lock().unwrap().push(WalkEntry {
+                            path: entry.into_path(),
+                            metadata: Self::convert(metadata),
+                        });
+                    }
+                    WalkState::Continue
+                })
+            });
+
+        let mut entries = entries.into_inner().unwrap();
+        if contents_first {
+            entries.sort_unstable_by(|a, b| Self::contents_first(&a.path, &b.path));
+        } else {
+            entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        }
+        entries
+    }
+
+    /// Output of a git command run in `root`; `None` when git cannot be run
+    /// or fails
+    fn git(root: &Path, args: &[&str]) -> Option<String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(args)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    }
+
+    /// Orders siblings by name and directories after everything inside them
+    fn contents_first(a: &Path, b: &Path) -> Ordering {
+        let mut a = a.components();
+        let mut b = b.components();
+        loop {
+            match (a.next(), b.next()) {
+                (Some(x), Some(y)) if x == y => continue,
+                (Some(x), Some(y)) => return x.cmp(&y),
+                // The longer path is inside the shorter one
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => return Ordering::Equal,
+            }
+        }
+    }
+}
+
+impl FileSystem for RealFs {
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::symlink_metadata(path).map(Self::convert)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::metadata(path).map(Self::convert)
+    }
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut children = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        children.sort();
+        Ok(children)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn repo_state(&self, root: &Path) -> Option<RepoState> {
+        let tracked = Self::git(root, &["ls-files", "-z"])?;
+        let status = Self::git(root, &["status", "--porcelain", "-z", "-uall"])?;
+        Some(RepoState::parse(&tracked, &status))
+    }
+
+    fn walk(&self, root: &Path, contents_first: bool) -> Vec<WalkEntry> {
+        // Symlinked roots keep the sequential walk, which follows them
+        let root_is_dir = fs::symlink_metadata(root).is_ok_and(|m| m.is_dir());
+        if self.threads > 1 && root_is_dir {
+            self.walk_parallel(root, contents_first)
+        } else {
+            Self::walk_sequential(root, contents_first).collect()
+        }
+    }
+
+    /// Always sequential, since sorting the output of the parallel walk
+    /// needs every entry first
+    fn walk_iter<'a>(
+        &'a self,
+        root: &Path,
+        contents_first: bool,
+    ) -> Box<dyn Iterator<Item = WalkEntry> + 'a> {
+        Box::new(Self::walk_sequential(root, contents_first))
+    }
+}
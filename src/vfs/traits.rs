@@ -0,0 +1,122 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::planner::{action::FsObjectKind, vcs::RepoState};
+
+#[doc = "What planners need to know about an entry"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub kind: FsObjectKind,
+    /// Size in bytes, zero for directories
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl Metadata {
+    pub fn is_dir(&self) -> bool {
+        self.kind == FsObjectKind::Directory
+    }
+}
+
+#[doc = "An entry found by [`FileSystem::walk`]"]
+#[derive(Debug, Clone)]
+pub struct WalkEntry {
+    pub path: PathBuf,
+    pub metadata: Metadata,
+}
+
+#[doc = "Read-only view of a file system tree that planners plan against"]
+pub trait FileSystem {
+    /// Metadata of `path` itself, without following symlinks
+    fn symlink_metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// Metadata of `path`, following symlinks
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// Paths of the entries directly inside `dir`, sorted by name
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Contents of the file at `path`, following symlinks
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Tracked and changed files of the git repository at `root`; `None`
+    /// when they cannot be listed
+    fn repo_state(&self, _root: &Path) -> Option<RepoState> {
+        None
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok_and(|m| m.is_dir())
+    }
+
+    /// Size of a file without following symlinks; zero for directories and
+    /// entries that cannot be read
+    fn file_size(&self, path: &Path) -> u64 {
+        self.symlink_metadata(path)
+            .map_or(0, |m| if m.is_dir() { 0 } else { m.len })
+    }
+
+    /// `root` and everything below it, sorted by name and without following
+    /// symlinks. With `contents_first`, directories come after their
+    /// contents. Unreadable entries are skipped.
+    fn walk(&self, root: &Path, contents_first: bool) -> Vec<WalkEntry> {
+        let mut entries = Vec::new();
+        if let Ok(metadata) = self.symlink_metadata(root) {
+            walk_into(
+                self,
+                root.to_path_buf(),
+                metadata,
+                contents_first,
+                &mut entries,
+            );
+        }
+        entries
+    }
+
+    /// Like [`FileSystem::walk`], but yields entries as they are read
+    /// instead of collecting the whole tree first
+    fn walk_iter<'a>(
+        &'a self,
+        root: &Path,
+        contents_first: bool,
+    ) -> Box<dyn Iterator<Item = WalkEntry> + 'a> {
+        Box::new(self.walk(root, contents_first).into_iter())
+    }
+}
+
+fn walk_into<F: FileSystem + ?Sized>(
+    fs: &F,
+    path: PathBuf,
+    metadata: Metadata,
+    contents_first: bool,
+    entries: &mut Vec<WalkEntry>,
+) {
+    if !metadata.is_dir() {
+        entries.push(WalkEntry { path, metadata });
+        return;
+    }
+
+    if !contents_first {
+        entries.push(WalkEntry {
+            path: path.clone(),
+            metadata,
+        });
+    }
+
+    for child in fs.read_dir(&path).unwrap_or_default() {
+        if let Ok(child_metadata) = fs.symlink_metadata(&child) {
+            walk_into(fs, child, child_metadata, contents_first, entries);
+        }
+    }
+
+    if contents_first {
+        entries.push(WalkEntry { path, metadata });
+    }
+}
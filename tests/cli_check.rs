@@ -0,0 +1,91 @@
+use std::fs;
+
+use assert_cmd::{Command, cargo};
+use tempfile::tempdir;
+
+/// Check_CleanPlan_NotExecuted
+///
+/// `elvis --check rm <file>`
+#[test]
+fn check_clean_plan_not_executed() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["--check", "rm", file.to_str().unwrap()])
+        .assert()
+        .code(0);
+
+    assert!(file.exists(), "File should be kept in check mode");
+}
+
+/// Check_PlanErrors_ExitCode
+///
+/// `elvis --plan-only rm <missing>`
+#[test]
+fn check_plan_errors_exit_code() {
+    let dir = tempdir().unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "--plan-only",
+        "rm",
+        dir.path().join("missing.txt").to_str().unwrap(),
+    ])
+    .assert()
+    .code(5);
+}
+
+/// Check_Warnings_OwnExitCodeOrFailOn
+///
+/// `elvis --check [--fail-on warnings] rm '*.none' <file>`
+#[test]
+fn check_warnings_own_exit_code_or_fail_on() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--check", "rm", "*.none", "a.txt"])
+        .assert()
+        .code(10)
+        .stderr(predicates::str::contains("1 warnings"));
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--check", "--fail-on", "warnings", "rm", "*.none", "a.txt"])
+        .assert()
+        .code(8)
+        .stderr(predicates::str::contains("1 warnings"));
+}
+
+/// Check_MaxActionsExceeded_ExitCode
+///
+/// `elvis --check --max-actions 1 rm -r <dir>`
+#[test]
+fn check_max_actions_exceeded_exit_code() {
+    let dir = tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    fs::create_dir(&sub).unwrap();
+    fs::write(sub.join("a.txt"), "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "--check",
+        "--max-actions",
+        "1",
+        "rm",
+        "-r",
+        sub.to_str().unwrap(),
+    ])
+    .assert()
+    .code(9);
+
+    // Limits also hold when executing
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["-y", "--max-bytes", "4", "rm", "-r", sub.to_str().unwrap()])
+        .assert()
+        .code(9);
+    assert!(sub.exists(), "Directory should be kept");
+}
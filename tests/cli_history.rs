@@ -0,0 +1,138 @@
+use std::fs;
+
+use assert_cmd::{Command, cargo};
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+/// A record as written when the audit log was introduced, before the summary
+/// and the actions gained their later fields
+const OLD_RECORD: &str = r#"{"time":{"secs_since_epoch":1792300000,"nanos_since_epoch":0},"user":"ops","host":"vm","cwd":"/srv","command_line":["elvis","-y","mv","old.txt","new.txt"],"command":"Mv","summary":{"files_deleted":0,"dirs_deleted":0,"files_created":0,"dirs_created":0,"files_moved":1,"files_modified":0,"files_copied":0,"links_created":0,"files_kept":0,"dirs_kept":0,"bytes_deleted":0,"bytes_moved":3,"bytes_copied":0,"bytes_overwritten":0,"warnings":0,"errors":0},"warnings":[],"applied":[{"Move":{"from":"/srv/old.txt","to":"/srv/new.txt","overwrite":false,"size":3}}],"failed":[],"skipped":[],"outcome":"succeeded"}"#;
+
+/// History_FilterByPath_FindsDeletion
+///
+/// `elvis history --path <file>` after two logged runs
+#[test]
+fn history_filter_by_path_finds_deletion() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    let work = dir.path().join("work");
+    fs::create_dir(&work).unwrap();
+    fs::write(work.join("prod.yaml"), "secret").unwrap();
+    fs::write(work.join("other.txt"), "x").unwrap();
+
+    for target in ["other.txt", "prod.yaml"] {
+        Command::new(cargo::cargo_bin!())
+            .current_dir(&work)
+            .env("ELVIS_AUDIT_LOG", &log)
+            .args(["-y", "--no-progress", "rm", target])
+            .assert()
+            .success();
+    }
+
+    Command::new(cargo::cargo_bin!())
+        .current_dir(&work)
+        .env("ELVIS_AUDIT_LOG", &log)
+        .args(["--no-color", "history", "--path", "prod.yaml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#2"))
+        .stdout(predicate::str::contains("rm prod.yaml"))
+        .stdout(predicate::str::contains("#1").not());
+}
+
+/// History_Show_RendersPlan
+///
+/// `elvis --audit-log <log> history show 1`
+#[test]
+fn history_show_renders_plan() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+    Command::new(cargo::cargo_bin!())
+        .current_dir(dir.path())
+        .args(["-y", "--no-progress", "--audit-log"])
+        .arg(&log)
+        .args(["rm", "a.txt"])
+        .assert()
+        .success();
+
+    Command::new(cargo::cargo_bin!())
+        .current_dir(dir.path())
+        .args(["--no-color", "--audit-log"])
+        .arg(&log)
+        .args(["history", "show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Run #1"))
+        .stdout(predicate::str::contains(
+            "Outcome: succeeded (1 applied, 0 failed, 0 skipped)",
+        ))
+        .stdout(predicate::str::contains("Delete:"))
+        .stdout(predicate::str::contains("a.txt"));
+
+    Command::new(cargo::cargo_bin!())
+        .args(["--audit-log"])
+        .arg(&log)
+        .args(["history", "show", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No recorded run #2"));
+}
+
+/// History_DamagedLines_KeepLineNumbers
+///
+/// `elvis history` on a log with an old record, then a blank and a broken
+/// line before a run
+#[test]
+fn history_damaged_lines_keep_line_numbers() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+    Command::new(cargo::cargo_bin!())
+        .current_dir(dir.path())
+        .args(["-y", "--no-progress", "--audit-log"])
+        .arg(&log)
+        .args(["rm", "a.txt"])
+        .assert()
+        .success();
+    let record = fs::read_to_string(&log).unwrap();
+    fs::write(&log, format!("{}\n\n{{\"truncated\n{}", OLD_RECORD, record)).unwrap();
+
+    Command::new(cargo::cargo_bin!())
+        .args(["--no-color", "--audit-log"])
+        .arg(&log)
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#1"))
+        .stdout(predicate::str::contains("mv old.txt new.txt"))
+        .stdout(predicate::str::contains("#4"))
+        .stdout(predicate::str::contains("rm a.txt"))
+        .stderr(predicate::str::contains("Skipped run #3"));
+
+    Command::new(cargo::cargo_bin!())
+        .args(["--no-color", "--audit-log"])
+        .arg(&log)
+        .args(["history", "show", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Run #4"));
+
+    Command::new(cargo::cargo_bin!())
+        .args(["--no-color", "--audit-log"])
+        .arg(&log)
+        .args(["history", "show", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old.txt -> new.txt"));
+
+    Command::new(cargo::cargo_bin!())
+        .args(["--audit-log"])
+        .arg(&log)
+        .args(["history", "show", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Recorded run #3 cannot be read"));
+}
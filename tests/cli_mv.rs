@@ -1,6 +1,7 @@
 use std::fs;
 
 use assert_cmd::{Command, cargo};
+use predicates::prelude::*;
 use tempfile::tempdir;
 
 // TODO: Nice-to-have test cases for `mv`:
@@ -195,3 +196,271 @@ fn mv_source_and_destination_are_same() {
         .assert()
         .failure();
 }
+
+/// Mv_FileOntoDirectory_Error
+///
+/// `elvis -y -- mv -T f d` and `elvis -y mv f d` with `d/f/` in the way
+#[test]
+fn mv_file_onto_directory_error() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("f"), "file").unwrap();
+    fs::create_dir_all(dir.path().join("d/f")).unwrap();
+    fs::write(dir.path().join("d/keep"), "keep").unwrap();
+    fs::write(dir.path().join("d/f/nested"), "nested").unwrap();
+
+    for args in [
+        &["-y", "--", "mv", "-T", "f", "d"][..],
+        &["-y", "mv", "f", "d"],
+    ] {
+        let mut cmd = Command::new(cargo::cargo_bin!());
+        cmd.current_dir(dir.path())
+            .args(["--no-color"])
+            .args(args)
+            .assert()
+            .code(5)
+            .stdout(predicates::str::contains(
+                "Cannot overwrite directory with non-directory",
+            ));
+    }
+
+    assert_eq!(fs::read_to_string(dir.path().join("f")).unwrap(), "file");
+    assert!(dir.path().join("d/keep").exists());
+    assert!(dir.path().join("d/f/nested").exists());
+}
+
+/// Mv_DirExclude_KeepsSource
+///
+/// `elvis -y mv --exclude <glob> <dir> <target>`
+#[test]
+fn mv_dir_exclude_keeps_source() {
+    let dir1 = tempdir().unwrap();
+    let src = dir1.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("a.txt"), "a").unwrap();
+    fs::write(src.join("b.tmp"), "b").unwrap();
+
+    let dir2 = tempdir().unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["-y", "mv", "--exclude", "*.tmp"])
+        .args([src.to_str().unwrap(), dir2.path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    assert!(dir2.path().join("src").join("a.txt").exists());
+    assert!(!dir2.path().join("src").join("b.tmp").exists());
+    assert!(src.join("b.tmp").exists(), "Excluded file stays in source");
+}
+
+/// Mv_FromStdinTargetDirectory_Success
+///
+/// `find ... | elvis -y mv --from-file - -t <dir>`
+#[test]
+fn mv_from_stdin_target_directory_success() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    fs::write(dir.path().join("b.txt"), "b").unwrap();
+    fs::create_dir(dir.path().join("dest")).unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "mv", "--from-file", "-", "-t", "dest"])
+        .write_stdin("a.txt\nb.txt\n")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("dest").join("a.txt").exists());
+    assert!(dir.path().join("dest").join("b.txt").exists());
+    assert!(!dir.path().join("a.txt").exists());
+}
+
+/// Mv_BackupSuffix_KeepsOverwritten
+///
+/// `elvis -y mv -S .bak <from> <existing>`
+#[test]
+fn mv_backup_suffix_keeps_overwritten() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("new.txt");
+    let to = dir.path().join("config.txt");
+    fs::write(&from, "new").unwrap();
+    fs::write(&to, "old").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args([
+            "-y",
+            "--no-color",
+            "mv",
+            "-S",
+            ".bak",
+            "new.txt",
+            "config.txt",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("config.txt -> config.txt.bak"));
+
+    assert!(!from.exists());
+    assert_eq!(fs::read_to_string(&to).unwrap(), "new");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("config.txt.bak")).unwrap(),
+        "old"
+    );
+}
+
+/// Mv_Backup_CountedApartFromMoves
+///
+/// `elvis --check --stats mv -b <from> <existing>`
+#[test]
+fn mv_backup_counted_apart_from_moves() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("new.txt"), "new").unwrap();
+    fs::write(dir.path().join("config.ini"), "old config").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args([
+            "--no-color",
+            "--check",
+            "--stats",
+            "mv",
+            "-b",
+            "new.txt",
+            "config.ini",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Move: 1 files (3 B)"))
+        .stdout(predicates::str::contains("Backup: 1 files"))
+        .stdout(predicates::str::contains("    .ini").not());
+}
+
+/// Mv_NoClobber_SkipsExisting
+///
+/// `elvis -y mv -f -n <from> <existing>`
+#[test]
+fn mv_no_clobber_skips_existing() {
+    let dir = tempdir().unwrap();
+    let from = dir.path().join("new.txt");
+    let to = dir.path().join("config.txt");
+    fs::write(&from, "new").unwrap();
+    fs::write(&to, "old").unwrap();
+
+    // -n given last wins over -f
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args([
+            "-y",
+            "--no-color",
+            "mv",
+            "-f",
+            "-n",
+            "new.txt",
+            "config.txt",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "new.txt -> config.txt (destination exists)",
+        ));
+
+    assert_eq!(fs::read_to_string(&from).unwrap(), "new");
+    assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+}
+
+/// Mv_Force_StillWarnsAboutOverwrite
+///
+/// `elvis --check mv -f <from> <existing>`
+#[test]
+fn mv_force_still_warns_about_overwrite() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("new.txt"), "new").unwrap();
+    fs::write(dir.path().join("config.txt"), "old").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "--check", "mv", "-f", "new.txt", "config.txt"])
+        .assert()
+        .code(10)
+        .stdout(predicates::str::contains("Dest will be overwrite"));
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("config.txt")).unwrap(),
+        "old"
+    );
+}
+
+/// Mv_UpdateOlder_ReplacesOlderOnly
+///
+/// `elvis -y -- mv -u <older> <newer> <dir>`
+#[test]
+fn mv_update_older_replaces_older_only() {
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target");
+    fs::create_dir(&target).unwrap();
+    for name in ["a.txt", "b.txt"] {
+        fs::write(dir.path().join(name), "source").unwrap();
+        fs::write(target.join(name), "target").unwrap();
+    }
+    let past = filetime::FileTime::from_unix_time(1_000_000, 0);
+    filetime::set_file_mtime(target.join("a.txt"), past).unwrap();
+    filetime::set_file_mtime(dir.path().join("b.txt"), past).unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args([
+            "-y",
+            "--no-color",
+            "--",
+            "mv",
+            "-u",
+            "a.txt",
+            "b.txt",
+            "target",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("(destination is not older)"));
+
+    assert_eq!(fs::read_to_string(target.join("a.txt")).unwrap(), "source");
+    assert_eq!(fs::read_to_string(target.join("b.txt")).unwrap(), "target");
+    assert!(dir.path().join("b.txt").exists());
+}
+
+/// Mv_GitignoredOnlyNested_Success
+///
+/// `elvis -y mv --gitignored-only src dst` with only `src/lib/build` ignored
+#[test]
+fn mv_gitignored_only_nested_success() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("lib/build")).unwrap();
+    fs::write(src.join("lib/build/out.o"), "obj").unwrap();
+    fs::write(src.join("lib/a.rs"), "a").unwrap();
+    fs::write(src.join("main.rs"), "main").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args([
+            "-y",
+            "--no-progress",
+            "mv",
+            "--gitignored-only",
+            "src",
+            "dst",
+        ])
+        .assert()
+        .success();
+
+    let dst = dir.path().join("dst");
+    assert_eq!(
+        fs::read_to_string(dst.join("lib/build/out.o")).unwrap(),
+        "obj"
+    );
+    assert!(!src.join("lib/build").exists(), "Ignored dir is moved");
+    assert!(src.join("lib/a.rs").exists(), "Other files stay");
+    assert!(src.join("main.rs").exists(), "Other files stay");
+    assert!(!dst.join("main.rs").exists());
+}
@@ -0,0 +1,206 @@
+use std::fs;
+
+use assert_cmd::{Command, cargo};
+use tempfile::tempdir;
+
+/// Preview_SectionOrder_Fixed
+///
+/// Create is always listed before Modify, regardless of argument order.
+///
+/// `elvis touch <existing> <new>`
+#[test]
+fn preview_section_order_fixed() {
+    let dir = tempdir().unwrap();
+    let existing = dir.path().join("existing.txt");
+    let new = dir.path().join("new.txt");
+    fs::write(&existing, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .args([
+            "--answer",
+            "no",
+            "touch",
+            existing.to_str().unwrap(),
+            new.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let create = stdout.find("Create:").expect("Create section");
+    let modify = stdout.find("Modify:").expect("Modify section");
+    assert!(create < modify, "Create should be printed before Modify");
+}
+
+/// Preview_SortByPath_Sorted
+///
+/// `elvis --sort path rm <c> <a> <b>`
+#[test]
+fn preview_sort_by_path_sorted() {
+    let dir = tempdir().unwrap();
+    let files: Vec<_> = ["c.txt", "a.txt", "b.txt"]
+        .iter()
+        .map(|name| dir.path().join(name))
+        .collect();
+    for file in files.iter() {
+        fs::write(file, "x").unwrap();
+    }
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--no-color", "--answer", "no", "--sort", "path", "rm"])
+        .args(["c.txt", "a.txt", "b.txt"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let a = stdout.find("a.txt").unwrap();
+    let b = stdout.find("b.txt").unwrap();
+    let c = stdout.find("c.txt").unwrap();
+    assert!(a < b && b < c, "Entries should be sorted by path");
+}
+
+/// Preview_SortBySizeReverse_LargestFirst
+///
+/// `elvis --sort size --reverse rm <small> <large>`
+#[test]
+fn preview_sort_by_size_reverse_largest_first() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("small.txt"), "x").unwrap();
+    fs::write(dir.path().join("large.txt"), "x".repeat(1024)).unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args([
+            "--no-color",
+            "--answer",
+            "no",
+            "--sort",
+            "size",
+            "--reverse",
+        ])
+        .args(["rm", "small.txt", "large.txt"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let large = stdout.find("large.txt").unwrap();
+    let small = stdout.find("small.txt").unwrap();
+    assert!(large < small, "Largest entry should be listed first");
+}
+
+/// Preview_TreeCollapsesDirectory_OneLine
+///
+/// `elvis --tree rm -r <dir> <file>`
+#[test]
+fn preview_tree_collapses_directory_one_line() {
+    let dir = tempdir().unwrap();
+    let modules = dir.path().join("node_modules");
+    fs::create_dir_all(modules.join("pkg")).unwrap();
+    fs::write(modules.join("pkg").join("index.js"), "x").unwrap();
+    fs::write(modules.join("a.js"), "x").unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src").join("keep.rs"), "x").unwrap();
+    fs::write(dir.path().join("src").join("gone.rs"), "x").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--no-color", "--answer", "no", "--tree", "rm", "-r"])
+        .args(["node_modules", "src/gone.rs"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("D  node_modules/ (2 files, 2 dirs, 2 B)"));
+    assert!(!stdout.contains("index.js"), "Collapsed entries are hidden");
+    assert!(
+        stdout.contains("   src/\n"),
+        "Partial directory is expanded"
+    );
+    assert!(stdout.contains("D    gone.rs"));
+    assert!(
+        !stdout.contains("keep.rs"),
+        "Untouched entries are not listed"
+    );
+}
+
+/// Preview_TreeMove_ListsMovedFiles
+///
+/// `elvis --tree mv src dst`
+#[test]
+fn preview_tree_move_lists_moved_files() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src/sub")).unwrap();
+    fs::write(dir.path().join("src/a.txt"), "abcd").unwrap();
+    fs::write(dir.path().join("src/sub/b.txt"), "abc").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--no-color", "--check", "--tree", "mv", "src", "dst"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("D  src/\n"), "Source is not collapsed");
+    assert!(stdout.contains("M    a.txt -> dst/a.txt"));
+    assert!(stdout.contains("M      b.txt -> dst/sub/b.txt"));
+    assert!(
+        !stdout.contains("D  src/ ("),
+        "Moved files are not counted as deleted"
+    );
+}
+
+/// Preview_Sizes_Shown
+///
+/// `elvis rm <file1> <file2>`
+#[test]
+fn preview_sizes_shown() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.bin"), vec![0u8; 1024]).unwrap();
+    fs::write(dir.path().join("b.bin"), vec![0u8; 2048]).unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--no-color", "--answer", "no", "rm", "a.bin", "b.bin"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("Delete: 2 files, 0 directories (3.0 KiB)"));
+    assert!(stdout.contains("D  a.bin (1.0 KiB)"));
+    assert!(stdout.contains("D  b.bin (2.0 KiB)"));
+}
+
+/// Preview_StatsJson_Included
+///
+/// `elvis --stats --json rm <files>`
+#[test]
+fn preview_stats_json_included() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.log"), "x".repeat(10)).unwrap();
+    fs::write(dir.path().join("b.log"), "x".repeat(20)).unwrap();
+    fs::write(dir.path().join("c.sqlite"), "x".repeat(300)).unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--answer", "no", "--stats", "--json", "rm"])
+        .args(["a.log", "b.log", "c.sqlite"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let stats = &json["stats"];
+    assert_eq!(stats["largest"][0]["path"], "c.sqlite");
+    assert_eq!(stats["largest"][0]["size"], 300);
+    assert_eq!(stats["by_extension"][".log"]["files"], 2);
+    assert_eq!(stats["by_extension"][".log"]["bytes"], 30);
+    assert_eq!(stats["by_extension"][".sqlite"]["files"], 1);
+    assert_eq!(json["plan"]["summary"]["files_deleted"], 3);
+}
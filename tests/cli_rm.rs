@@ -1,10 +1,13 @@
 use assert_cmd::{Command, cargo};
-use std::fs;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    process::Stdio,
+};
 use tempfile::tempdir;
 
 // TODO: Nice-to-have test cases for `rm`:
 // - `rm_no_permissions`: Try to remove a file without permissions.
-// - `rm_interactive_confirmation_no`: Run without `-y` and pipe "n" to stdin to test cancellation.
 
 /// Rm_File_Success
 ///
@@ -103,3 +106,428 @@ fn rm_empty_dir_without_recursive_flag_success() {
 
     assert!(!empty_dir.exists(), "Empty directory should be removed");
 }
+
+/// Rm_StdinAnswerNo_Cancel
+///
+/// `echo n | elvis rm <file>`
+#[test]
+fn rm_stdin_answer_no_cancel() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["rm", file.to_str().unwrap()])
+        .write_stdin("n\n")
+        .assert()
+        .code(4);
+
+    assert!(file.exists(), "File should be kept after cancel");
+}
+
+/// Rm_StdinAnswerYes_Success
+///
+/// `echo y | elvis rm <file>`
+#[test]
+fn rm_stdin_answer_yes_success() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["rm", file.to_str().unwrap()])
+        .write_stdin("yes\n")
+        .assert()
+        .success();
+
+    assert!(!file.exists(), "File should be removed");
+}
+
+/// Rm_NoTtyWithoutYes_Error
+///
+/// `elvis rm <file> < /dev/null`
+#[test]
+fn rm_no_tty_without_yes_error() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["rm", file.to_str().unwrap()])
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("-y"));
+
+    assert!(file.exists(), "File should be kept without confirmation");
+}
+
+/// Rm_AnswerFlag_Success
+///
+/// `elvis --answer yes rm <file>`
+#[test]
+fn rm_answer_flag_success() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["--answer", "yes", "rm", file.to_str().unwrap()])
+        .assert()
+        .success();
+
+    assert!(!file.exists(), "File should be removed");
+}
+
+/// Rm_RecursiveExclude_KeepsParents
+///
+/// `elvis -y rm -r --exclude <glob> <dir>`
+#[test]
+fn rm_recursive_exclude_keeps_parents() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("build");
+    let nested = root.join("nested");
+    fs::create_dir_all(&nested).unwrap();
+    let kept = nested.join("important.keep");
+    let removed = nested.join("a.o");
+    let other = root.join("b.o");
+    fs::write(&kept, "keep").unwrap();
+    fs::write(&removed, "x").unwrap();
+    fs::write(&other, "x").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "-y",
+        "rm",
+        "-r",
+        "--exclude",
+        "*.keep",
+        root.to_str().unwrap(),
+    ])
+    .assert()
+    .success()
+    .stdout(predicates::str::contains(
+        "Keep: 1 files, 2 directories (filtered)",
+    ));
+
+    assert!(kept.exists(), "Excluded file should be kept");
+    assert!(!removed.exists(), "Other files should be removed");
+    assert!(!other.exists(), "Other files should be removed");
+}
+
+/// Rm_RecursiveIncludeAndExcludeFrom_Success
+///
+/// `elvis -y rm -r --include <glob> --exclude-from <file> <dir>`
+#[test]
+fn rm_recursive_include_and_exclude_from_success() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("logs");
+    let archive = root.join("archive");
+    fs::create_dir_all(&archive).unwrap();
+    fs::write(root.join("a.log"), "x").unwrap();
+    fs::write(root.join("notes.txt"), "x").unwrap();
+    fs::write(archive.join("old.log"), "x").unwrap();
+
+    let patterns = dir.path().join("patterns");
+    fs::write(&patterns, "# keep archives\narchive\n").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["-y", "rm", "-r", "--include", "*.log", "--exclude-from"])
+        .args([patterns.to_str().unwrap(), root.to_str().unwrap()])
+        .assert()
+        .success();
+
+    assert!(
+        !root.join("a.log").exists(),
+        "Included file should be removed"
+    );
+    assert!(
+        root.join("notes.txt").exists(),
+        "Other files should be kept"
+    );
+    assert!(
+        archive.join("old.log").exists(),
+        "Excluded dir should be kept"
+    );
+}
+
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=elvis", "-c", "user.email=elvis@localhost"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Rm_GitignoredOnly_Success
+///
+/// `elvis -y rm -r --gitignored-only <dir>`
+#[test]
+fn rm_gitignored_only_success() {
+    let dir = tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    fs::write(dir.path().join(".gitignore"), "target/\n*.tmp\n").unwrap();
+    fs::create_dir(dir.path().join("target")).unwrap();
+    fs::write(dir.path().join("target").join("out.o"), "x").unwrap();
+    fs::write(dir.path().join("scratch.tmp"), "x").unwrap();
+    fs::write(dir.path().join("main.rs"), "x").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "rm", "-r", "--gitignored-only", "."])
+        .assert()
+        .success();
+
+    assert!(
+        !dir.path().join("target").exists(),
+        "Ignored dir is removed"
+    );
+    assert!(
+        !dir.path().join("scratch.tmp").exists(),
+        "Ignored file is removed"
+    );
+    assert!(dir.path().join("main.rs").exists(), "Other files are kept");
+    assert!(
+        dir.path().join(".gitignore").exists(),
+        "Other files are kept"
+    );
+    assert!(dir.path().join(".git").exists(), "Repository is kept");
+}
+
+/// Rm_GitTrackedAndModified_Warning
+///
+/// `elvis rm <tracked> <modified>`
+#[test]
+fn rm_git_tracked_and_modified_warning() {
+    let dir = tempdir().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    fs::write(dir.path().join("clean.rs"), "x").unwrap();
+    fs::write(dir.path().join("dirty.rs"), "x").unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    fs::write(dir.path().join("dirty.rs"), "changed").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--answer", "no", "rm", "clean.rs", "dirty.rs"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "1 files with uncommitted changes will be lost",
+        ))
+        .stdout(predicates::str::contains(
+            "1 files tracked by git will be removed",
+        ));
+}
+
+/// Rm_FromStdinNul_Success
+///
+/// `printf 'a\0b\0' | elvis -y rm --from-file - -0`
+#[test]
+fn rm_from_stdin_nul_success() {
+    let dir = tempdir().unwrap();
+    let file1 = dir.path().join("a b.txt");
+    let file2 = dir.path().join("c.txt");
+    fs::write(&file1, "a").unwrap();
+    fs::write(&file2, "b").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "rm", "--from-file", "-", "-0"])
+        .write_stdin("a b.txt\0c.txt\0")
+        .assert()
+        .success();
+
+    assert!(!file1.exists(), "File 1 should be removed");
+    assert!(!file2.exists(), "File 2 should be removed");
+}
+
+/// Rm_QuotedGlob_Expanded
+///
+/// `elvis -y rm '**/*.log' '*.none'`
+#[test]
+fn rm_quoted_glob_expanded() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("a.log"), "a").unwrap();
+    fs::write(dir.path().join("sub").join("b.log"), "b").unwrap();
+    fs::write(dir.path().join("keep.txt"), "c").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "-y", "rm", "**/*.log", "*.none"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("**/*.log (2 matches)"))
+        .stdout(predicates::str::contains(
+            "Pattern `*.none` matched nothing",
+        ));
+
+    assert!(
+        !dir.path().join("a.log").exists(),
+        "Matched file is removed"
+    );
+    assert!(
+        !dir.path().join("sub").join("b.log").exists(),
+        "Nested match is removed"
+    );
+    assert!(dir.path().join("keep.txt").exists(), "Other files are kept");
+}
+
+/// Rm_QuotedGlob_SkipsDotfiles
+///
+/// `elvis -y rm -r '*'`
+#[test]
+fn rm_quoted_glob_skips_dotfiles() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(dir.path().join(".env"), "secret").unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "-y", "rm", "-r", "*"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("* (1 match)"));
+
+    assert!(!dir.path().join("a.txt").exists());
+    assert!(dir.path().join(".git").exists(), "Dot directory is kept");
+    assert!(dir.path().join(".env").exists(), "Dotfile is kept");
+}
+
+/// Rm_ChangedBeforeConfirmation_Aborts
+///
+/// `echo y | elvis rm a.txt` while a.txt grows before the answer
+#[test]
+fn rm_changed_before_confirmation_aborts() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "abc").unwrap();
+
+    let mut child = std::process::Command::new(cargo::cargo_bin!())
+        .current_dir(dir.path())
+        .args(["--no-color", "rm", "a.txt"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Elvis waits for the answer once the preview is out
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut preview = String::new();
+    while !preview.contains("D  a.txt (3 B)") {
+        assert_ne!(stdout.read_line(&mut preview).unwrap(), 0, "{}", preview);
+    }
+    fs::write(&file, "grown after preview").unwrap();
+    child.stdin.take().unwrap().write_all(b"y\n").unwrap();
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = preview + &rest;
+    assert_eq!(output.status.code(), Some(7));
+    assert!(stdout.contains("0 succeeded, 1 failed, 0 skipped"));
+    assert!(stdout.contains("changed action 1: delete a.txt - Changed since preview"));
+    assert!(file.exists());
+}
+
+/// Rm_RecursiveParallel_Success
+///
+/// `elvis -y -j 4 rm -r <dir>`
+#[test]
+fn rm_recursive_parallel_success() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("tree");
+    for sub in ["a", "b", "c", "d"] {
+        fs::create_dir_all(root.join(sub).join("nested")).unwrap();
+        for i in 0..10 {
+            fs::write(
+                root.join(sub).join("nested").join(format!("{}.txt", i)),
+                "x",
+            )
+            .unwrap();
+        }
+    }
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "-y", "-j", "4", "rm", "-r", "tree"])
+        .assert()
+        .success();
+
+    assert!(!root.exists());
+}
+
+/// Rm_RecursiveStream_Success
+///
+/// `elvis -y --stream --max-entries 2 rm -r <dir>`
+#[test]
+fn rm_recursive_stream_success() {
+    let dir = tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    fs::create_dir_all(sub.join("nested")).unwrap();
+    for name in ["a.txt", "b.txt", "nested/c.txt"] {
+        fs::write(sub.join(name), "content").unwrap();
+    }
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "-y",
+        "--no-progress",
+        "--stream",
+        "--max-entries",
+        "2",
+        "rm",
+        "-r",
+        sub.to_str().unwrap(),
+    ])
+    .assert()
+    .success()
+    .stdout(predicates::str::contains("3 more actions not previewed"));
+
+    assert!(!sub.exists(), "Directory should be removed");
+}
+
+/// Rm_StreamWithJobs_Error
+///
+/// `elvis -y --stream -j 4 rm <file>`
+#[test]
+fn rm_stream_with_jobs_error() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args(["-y", "--stream", "-j", "4", "rm", file.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be combined with --jobs"));
+
+    assert!(file.exists(), "File should be left in place");
+}
+
+/// Mv_Stream_Error
+///
+/// `elvis --stream mv <src> <dst>`
+#[test]
+fn mv_stream_error() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "hello").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "-y",
+        "--stream",
+        "mv",
+        file.to_str().unwrap(),
+        dir.path().join("b.txt").to_str().unwrap(),
+    ])
+    .assert()
+    .failure();
+
+    assert!(file.exists(), "File should be left in place");
+}
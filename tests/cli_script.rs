@@ -0,0 +1,73 @@
+use std::fs;
+
+use assert_cmd::{Command, cargo};
+use tempfile::tempdir;
+
+/// Script_SequentialCommands_Success
+///
+/// `elvis -y script <file.sh>`
+#[test]
+fn script_sequential_commands_success() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("app.conf"), "cfg").unwrap();
+    fs::create_dir(dir.path().join("old")).unwrap();
+    fs::write(dir.path().join("old").join("a.txt"), "a").unwrap();
+    fs::write(
+        dir.path().join("deploy.sh"),
+        "# deploy\n\
+         mkdir -p release/bin\n\
+         cp app.conf release/ && cp -r old release/old\n\
+         mv release/old release/legacy\n\
+         touch release/bin/run\n\
+         rm -rf old\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "-y", "script", "deploy.sh"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Copy: 2 files"));
+
+    let release = dir.path().join("release");
+    assert!(release.join("app.conf").exists(), "File should be copied");
+    assert!(
+        release.join("legacy").join("a.txt").exists(),
+        "Copy should be moved"
+    );
+    assert!(
+        release.join("bin").join("run").exists(),
+        "File should be created"
+    );
+    assert!(
+        !dir.path().join("old").exists(),
+        "Directory should be removed"
+    );
+}
+
+/// Script_FailingCommand_Error
+///
+/// A command that depends on an earlier `rm` is flagged with its line.
+///
+/// `elvis script <file.sh>`
+#[test]
+fn script_failing_command_error() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    fs::write(dir.path().join("run.sh"), "rm a.txt\nmv a.txt b.txt\n").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "script", "run.sh"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "line 2: mv: No such file or directory",
+        ));
+
+    assert!(
+        dir.path().join("a.txt").exists(),
+        "Nothing should be executed"
+    );
+}
@@ -98,3 +98,32 @@ fn touch_updates_modification_time() {
         "Modification time should be updated"
     );
 }
+
+/// Touch_FromFile_Success
+///
+/// `elvis -y touch --from-file <list> <file>`
+#[test]
+fn touch_from_file_success() {
+    let dir = tempdir().unwrap();
+    let list = dir.path().join("list.txt");
+    fs::write(&list, "a.txt\r\nb.txt\n\n").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "touch", "--from-file", "list.txt", "c.txt"])
+        .assert()
+        .success();
+
+    assert!(
+        dir.path().join("a.txt").exists(),
+        "Listed file should be created"
+    );
+    assert!(
+        dir.path().join("b.txt").exists(),
+        "Listed file should be created"
+    );
+    assert!(
+        dir.path().join("c.txt").exists(),
+        "Argument should be created"
+    );
+}
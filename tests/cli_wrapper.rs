@@ -0,0 +1,120 @@
+use std::fs;
+
+use assert_cmd::{Command, cargo};
+use tempfile::tempdir;
+
+/// Wrapper_RmCombinedFlags_Success
+///
+/// `elvis -y -- rm -rf <dir> <missing>`
+#[test]
+fn wrapper_rm_combined_flags_success() {
+    let dir = tempdir().unwrap();
+    let build = dir.path().join("build");
+    fs::create_dir(&build).unwrap();
+    fs::write(build.join("a.o"), "x").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "--", "rm", "-rf", "build/", "missing.txt"])
+        .assert()
+        .success();
+
+    assert!(!build.exists(), "Directory should be removed recursively");
+}
+
+/// Wrapper_MvTargetDirectory_Success
+///
+/// `elvis -y -- mv -t <dir> -- <file1> -file2`
+#[test]
+fn wrapper_mv_target_directory_success() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("dest")).unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    fs::write(dir.path().join("-b.txt"), "b").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "--", "mv", "-tdest", "--", "a.txt", "-b.txt"])
+        .assert()
+        .success();
+
+    assert!(dir.path().join("dest").join("a.txt").exists());
+    assert!(dir.path().join("dest").join("-b.txt").exists());
+}
+
+/// Wrapper_MvNoTargetDirectory_Renames
+///
+/// `elvis -y -- mv -T <dir> <existing_empty_dir>`
+#[test]
+fn wrapper_mv_no_target_directory_renames() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("src");
+    let dest = dir.path().join("dest");
+    fs::create_dir(&src).unwrap();
+    fs::create_dir(&dest).unwrap();
+    fs::write(src.join("a.txt"), "a").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "--", "mv", "-T", "src", "dest"])
+        .assert()
+        .success();
+
+    assert!(
+        dest.join("a.txt").exists(),
+        "Contents should land in dest itself"
+    );
+    assert!(!dest.join("src").exists(), "Source should not be nested");
+    assert!(!src.exists(), "Source should be removed");
+}
+
+/// Wrapper_UnknownFlag_NotPreviewable
+///
+/// `elvis -- rm --one-file-system <dir>`
+#[test]
+fn wrapper_unknown_flag_not_previewable() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    fs::write(&file, "a").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.args([
+        "-y",
+        "--",
+        "rm",
+        "--one-file-system",
+        file.to_str().unwrap(),
+    ])
+    .assert()
+    .failure()
+    .stderr(predicates::str::contains("Not previewable"));
+
+    assert!(file.exists(), "Nothing should be removed");
+}
+
+/// Wrapper_MvBackupNumbered_Success
+///
+/// `elvis -y -- mv --backup=numbered <file> <existing>`
+#[test]
+fn wrapper_mv_backup_numbered_success() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "new").unwrap();
+    fs::write(dir.path().join("b.txt"), "old").unwrap();
+    fs::write(dir.path().join("b.txt.~1~"), "older").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["-y", "--", "mv", "--backup=numbered", "a.txt", "b.txt"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "new");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("b.txt.~1~")).unwrap(),
+        "older"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("b.txt.~2~")).unwrap(),
+        "old"
+    );
+}
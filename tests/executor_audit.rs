@@ -0,0 +1,252 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use elvis::{
+    error::Error,
+    executor::{
+        audit::{AuditLog, AuditRecord, AuditSink},
+        execution::Executor,
+        options::ExecutorOptions,
+        report::ExecutionReport,
+    },
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary},
+        rm::RmPlanner,
+        stream::{PlanBuilder, PlanSpool, StreamingPlanner},
+    },
+    vfs::real::RealFs,
+};
+use serde_json::Value;
+use tempfile::tempdir;
+
+fn plan(cwd: PathBuf, actions: Vec<Action>) -> Plan {
+    Plan {
+        metadata: PlanMetadata {
+            command: CommandKind::Script,
+            working_dir: cwd,
+            created_at: SystemTime::now(),
+        },
+        actions,
+        warnings: Vec::new(),
+        errors: Vec::new(),
+        summary: PlanSummary::default(),
+        patterns: Vec::new(),
+        skipped: Vec::new(),
+        omitted: 0,
+    }
+}
+
+/// Execute_AuditLog_RecordsAppliedAndFailed
+#[test]
+fn execute_audit_log_records_applied_and_failed() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    let cwd = dir.path().join("work");
+    fs::create_dir(&cwd).unwrap();
+
+    let options = ExecutorOptions {
+        assume_yes: true,
+        keep_going: true,
+        audit: Some(AuditLog::new(
+            AuditSink::File(log.clone()),
+            vec!["elvis".into(), "script".into(), "run.sh".into()],
+        )),
+        ..Default::default()
+    };
+    let actions = vec![
+        Action::Create {
+            path: cwd.join("a.txt"),
+            kind: FsObjectKind::File,
+        },
+        Action::Create {
+            path: cwd.join("missing/b.txt"),
+            kind: FsObjectKind::File,
+        },
+    ];
+
+    Executor::execute(&plan(cwd.clone(), actions), &options).unwrap();
+    Executor::execute(&plan(cwd.clone(), Vec::new()), &options).unwrap();
+
+    let content = fs::read_to_string(&log).unwrap();
+    let records: Vec<Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+
+    let record = &records[0];
+    assert_eq!(record["outcome"], "failed");
+    assert_eq!(record["command"], "Script");
+    assert_eq!(record["command_line"][2], "run.sh");
+    assert_eq!(record["cwd"], cwd.to_str().unwrap());
+    assert!(record["user"].is_string() && record["host"].is_string());
+    assert_eq!(
+        record["applied"][0]["Create"]["path"],
+        cwd.join("a.txt").to_str().unwrap()
+    );
+    assert_eq!(record["failed"].as_array().unwrap().len(), 1);
+    assert_eq!(records[1]["outcome"], "succeeded");
+}
+
+/// ExecuteSpooled_KeepGoing_RecordsActionsFromSpool
+#[test]
+fn execute_spooled_keep_going_records_actions_from_spool() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    let work = dir.path().join("work");
+    fs::create_dir_all(work.join("sub")).unwrap();
+    for name in ["c.txt", "sub/a.txt", "sub/b.txt"] {
+        fs::write(work.join(name), "abc").unwrap();
+    }
+
+    let fs = RealFs::default();
+    let planner = RmPlanner::new(vec![work.clone()], true, false, dir.path().to_path_buf());
+    let spool = PlanSpool::collect(
+        PlanBuilder::new(&fs, CommandKind::Rm, dir.path().to_path_buf()),
+        planner.stream(&fs),
+        1,
+    )
+    .unwrap();
+    // Deleting a.txt fails, which leaves sub and work in place
+    fs::write(work.join("sub/a.txt"), "grown").unwrap();
+
+    let options = ExecutorOptions {
+        assume_yes: true,
+        keep_going: true,
+        audit: Some(AuditLog::new(AuditSink::File(log.clone()), Vec::new())),
+        ..Default::default()
+    };
+    let report = Executor::execute_spooled(&spool, &options, &mut |_| {}).unwrap();
+
+    assert_eq!(report.succeeded, vec![0, 2]);
+    let skipped: Vec<(usize, usize)> = report.skipped.iter().map(|s| (s.index, s.cause)).collect();
+    assert_eq!(skipped, vec![(3, 1), (4, 3)]);
+
+    let record: Value = serde_json::from_str(&fs::read_to_string(&log).unwrap()).unwrap();
+    let paths = |list: &Value| -> Vec<String> {
+        list.as_array()
+            .unwrap()
+            .iter()
+            .map(|action| {
+                let action = action.get("action").unwrap_or(action);
+                action["Delete"]["path"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+    let path = |name: &str| work.join(name).to_str().unwrap().to_string();
+    let root = work.to_str().unwrap().to_string();
+    assert_eq!(
+        paths(&record["applied"]),
+        vec![path("c.txt"), path("sub/b.txt")]
+    );
+    assert_eq!(paths(&record["failed"]), vec![path("sub/a.txt")]);
+    assert_eq!(paths(&record["skipped"]), vec![path("sub"), root]);
+    assert!(work.join("sub/a.txt").exists());
+}
+
+/// Execute_AuditLogUnwritable_NothingApplied
+#[test]
+fn execute_audit_log_unwritable_nothing_applied() {
+    let dir = tempdir().unwrap();
+    let options = ExecutorOptions {
+        assume_yes: true,
+        audit: Some(AuditLog::new(
+            AuditSink::File(dir.path().join("missing/audit.jsonl")),
+            Vec::new(),
+        )),
+        ..Default::default()
+    };
+    let file = dir.path().join("a.txt");
+    let actions = vec![Action::Create {
+        path: file.clone(),
+        kind: FsObjectKind::File,
+    }];
+
+    assert!(Executor::execute(&plan(dir.path().to_path_buf(), actions), &options).is_err());
+    assert!(!file.exists());
+}
+
+/// Execute_AuditLogCancelled_NotCreated
+#[test]
+fn execute_audit_log_cancelled_not_created() {
+    let dir = tempdir().unwrap();
+    let log = dir.path().join("audit.jsonl");
+    let options = ExecutorOptions {
+        answer: Some(false),
+        audit: Some(AuditLog::new(AuditSink::File(log.clone()), Vec::new())),
+        ..Default::default()
+    };
+    let actions = vec![Action::Create {
+        path: dir.path().join("a.txt"),
+        kind: FsObjectKind::File,
+    }];
+
+    let result = Executor::execute(&plan(dir.path().to_path_buf(), actions), &options);
+    assert!(matches!(result, Err(Error::Cancelled)));
+    assert!(!log.exists(), "Log should not be created");
+}
+
+/// Execute_AuditLogFailsAfterApplying_ReportKept
+#[test]
+fn execute_audit_log_fails_after_applying_report_kept() {
+    let dir = tempdir().unwrap();
+    let logs = dir.path().join("logs");
+    fs::create_dir(&logs).unwrap();
+    let options = ExecutorOptions {
+        assume_yes: true,
+        audit: Some(AuditLog::new(
+            AuditSink::File(logs.join("audit.jsonl")),
+            Vec::new(),
+        )),
+        ..Default::default()
+    };
+    // Moving the log directory away makes the record unwritable
+    let actions = vec![Action::Move {
+        from: logs.clone(),
+        to: dir.path().join("moved"),
+        kind: FsObjectKind::Directory,
+        overwrite: None,
+        size: 0,
+        modified: None,
+        backup: false,
+    }];
+
+    let report = Executor::execute(&plan(dir.path().to_path_buf(), actions), &options).unwrap();
+
+    assert_eq!(report.succeeded, vec![0]);
+    assert!(report.audit_error.is_some());
+    let err = report.into_error().unwrap();
+    assert!(matches!(err, Error::AuditFailed(_)));
+    assert_eq!(err.exit_code(), 11);
+}
+
+/// AuditRecord_Summarize_CountsAndShortCommandLine
+#[test]
+fn audit_record_summarize_counts_and_short_command_line() {
+    let dir = tempdir().unwrap();
+    let log = AuditLog::new(AuditSink::Syslog, vec!["elvis".into(), "x".repeat(5000)]);
+    let actions = vec![Action::Create {
+        path: dir.path().join("a.txt"),
+        kind: FsObjectKind::File,
+    }];
+    let report = ExecutionReport {
+        succeeded: vec![0],
+        ..Default::default()
+    };
+
+    let record = AuditRecord::new(
+        &log,
+        &plan(dir.path().to_path_buf(), actions.clone()),
+        &actions,
+        &report,
+    );
+    let summary = record.summarize();
+
+    assert_eq!(summary.command_line.chars().count(), 1024 + 3);
+    assert!(summary.command_line.starts_with("elvis xxx") && summary.command_line.ends_with("..."));
+    assert_eq!(
+        (summary.applied, summary.failed, summary.skipped),
+        (1, 0, 0)
+    );
+    assert!(serde_json::to_string(&summary).unwrap().len() < 2048);
+}
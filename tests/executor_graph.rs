@@ -0,0 +1,91 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use elvis::{
+    executor::graph::DependencyGraph,
+    planner::{
+        action::{Action, FsObjectKind},
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary},
+    },
+};
+
+fn plan(actions: Vec<Action>) -> Plan {
+    Plan {
+        metadata: PlanMetadata {
+            command: CommandKind::Mv,
+            working_dir: PathBuf::from("/p"),
+            created_at: SystemTime::now(),
+        },
+        actions,
+        warnings: Vec::new(),
+        errors: Vec::new(),
+        summary: PlanSummary::default(),
+        patterns: Vec::new(),
+        skipped: Vec::new(),
+        omitted: 0,
+    }
+}
+
+fn create(path: &str) -> Action {
+    Action::Create {
+        path: PathBuf::from(path),
+        kind: FsObjectKind::Directory,
+    }
+}
+
+fn mv(from: &str, to: &str) -> Action {
+    Action::Move {
+        from: PathBuf::from(from),
+        to: PathBuf::from(to),
+        kind: FsObjectKind::File,
+        overwrite: None,
+        size: 1,
+        modified: None,
+        backup: false,
+    }
+}
+
+fn delete(path: &str) -> Action {
+    Action::Delete {
+        path: PathBuf::from(path),
+        kind: FsObjectKind::Directory,
+        recursive: false,
+        size: 0,
+        modified: None,
+    }
+}
+
+/// DependencyGraph_MvTree_OrdersRelatedPaths
+#[test]
+fn dependency_graph_mv_tree_orders_related_paths() {
+    let graph = DependencyGraph::from_plan(&plan(vec![
+        create("/p/dst"),                   // 0
+        create("/p/dst/sub"),               // 1
+        mv("/p/src/a", "/p/dst/a"),         // 2
+        mv("/p/src/sub/b", "/p/dst/sub/b"), // 3
+        delete("/p/src/sub"),               // 4
+        delete("/p/src"),                   // 5
+    ]));
+
+    assert_eq!(
+        graph.dependencies,
+        vec![vec![], vec![0], vec![0], vec![1], vec![3], vec![2, 4],]
+    );
+    assert_eq!(graph.dependents[0], vec![1, 2]);
+}
+
+/// DependencyGraph_UnrelatedSubtrees_Independent
+#[test]
+fn dependency_graph_unrelated_subtrees_independent() {
+    let graph = DependencyGraph::from_plan(&plan(vec![
+        delete("/p/a/x"),
+        delete("/p/b/y"),
+        delete("/p/a"),
+        delete("/p/b"),
+        delete("/p"),
+    ]));
+
+    assert_eq!(
+        graph.dependencies,
+        vec![vec![], vec![], vec![0], vec![1], vec![2, 3]]
+    );
+}
@@ -0,0 +1,183 @@
+use std::{fs, path::PathBuf, sync::mpsc, time::SystemTime};
+
+use elvis::{
+    error::Error,
+    executor::{
+        execution::Executor,
+        options::ExecutorOptions,
+        progress::{Progress, ProgressEvent},
+    },
+    planner::{
+        action::{Action, FsObjectKind},
+        mv::MvPlanner,
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary},
+        traits::Planner,
+    },
+    vfs::real::RealFs,
+};
+use tempfile::tempdir;
+
+fn plan(cwd: PathBuf, actions: Vec<Action>) -> Plan {
+    Plan {
+        metadata: PlanMetadata {
+            command: CommandKind::Script,
+            working_dir: cwd,
+            created_at: SystemTime::now(),
+        },
+        actions,
+        warnings: Vec::new(),
+        errors: Vec::new(),
+        summary: PlanSummary::default(),
+        patterns: Vec::new(),
+        skipped: Vec::new(),
+        omitted: 0,
+    }
+}
+
+fn failing_plan(cwd: PathBuf) -> Plan {
+    plan(
+        cwd.clone(),
+        vec![
+            Action::Create {
+                path: cwd.join("missing/a.txt"),
+                kind: FsObjectKind::File,
+            },
+            Action::Create {
+                path: cwd.join("b.txt"),
+                kind: FsObjectKind::File,
+            },
+            Action::Modify {
+                path: cwd.join("missing/a.txt"),
+                kind: FsObjectKind::File,
+                description: "Update modification time".into(),
+                modified: None,
+            },
+        ],
+    )
+}
+
+/// Execute_KeepGoing_SkipsDependents
+#[test]
+fn execute_keep_going_skips_dependents() {
+    let dir = tempdir().unwrap();
+    let options = ExecutorOptions {
+        assume_yes: true,
+        keep_going: true,
+        ..Default::default()
+    };
+
+    let report = Executor::execute(&failing_plan(dir.path().to_path_buf()), &options).unwrap();
+
+    assert_eq!(report.succeeded, vec![1]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].index, 0);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!((report.skipped[0].index, report.skipped[0].cause), (2, 0));
+    assert!(dir.path().join("b.txt").exists());
+    assert!(matches!(
+        report.into_error(),
+        Some(Error::ActionFailed { action, .. }) if matches!(*action, Action::Create { .. })
+    ));
+}
+
+/// Execute_WithoutKeepGoing_RefusesFailingPlan
+#[test]
+fn execute_without_keep_going_refuses_failing_plan() {
+    let dir = tempdir().unwrap();
+    let options = ExecutorOptions {
+        assume_yes: true,
+        ..Default::default()
+    };
+
+    let err = Executor::execute(&failing_plan(dir.path().to_path_buf()), &options).unwrap_err();
+
+    assert!(matches!(err, Error::PlanInvalid(_)));
+    assert_eq!(err.exit_code(), 5);
+    assert!(err.to_string().contains("Simulated execution failed"));
+    assert!(fs::read_dir(dir.path()).unwrap().next().is_none());
+}
+
+/// Execute_OverwrittenFileBecameDirectory_DriftKeepsTree
+#[test]
+fn execute_overwritten_file_became_directory_drift_keeps_tree() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "abc").unwrap();
+    fs::write(dir.path().join("b.txt"), "old").unwrap();
+    let plan = MvPlanner::new(
+        vec![dir.path().join("a.txt")],
+        dir.path().join("b.txt"),
+        true,
+        dir.path().to_path_buf(),
+    )
+    .plan(&RealFs::default());
+
+    // The file planned to be overwritten is replaced by a whole tree
+    fs::remove_file(dir.path().join("b.txt")).unwrap();
+    fs::create_dir_all(dir.path().join("b.txt/keep")).unwrap();
+    let options = ExecutorOptions {
+        assume_yes: true,
+        keep_going: true,
+        ..Default::default()
+    };
+    let report = Executor::execute(&plan, &options).unwrap();
+
+    assert!(report.succeeded.is_empty());
+    assert!(matches!(
+        report.into_error(),
+        Some(Error::DriftDetected(drift)) if drift.path == dir.path().join("b.txt")
+    ));
+    assert!(dir.path().join("a.txt").exists());
+    assert!(dir.path().join("b.txt/keep").is_dir());
+}
+
+/// ExecuteWithProgress_Channel_ReportsEveryAction
+#[test]
+fn execute_with_progress_channel_reports_every_action() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "abcd").unwrap();
+    let plan = plan(
+        dir.path().to_path_buf(),
+        vec![
+            Action::Create {
+                path: dir.path().join("new"),
+                kind: FsObjectKind::Directory,
+            },
+            Action::Move {
+                from: dir.path().join("a.txt"),
+                to: dir.path().join("new/a.txt"),
+                kind: FsObjectKind::File,
+                overwrite: None,
+                size: 4,
+                modified: None,
+                backup: false,
+            },
+        ],
+    );
+    let options = ExecutorOptions {
+        assume_yes: true,
+        ..Default::default()
+    };
+
+    let (mut sender, receiver) = mpsc::channel();
+    Executor::execute_with_progress(&plan, &options, &mut sender).unwrap();
+    let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+
+    assert_eq!(events.len(), 4);
+    assert!(matches!(
+        events[0],
+        ProgressEvent::Started(Progress {
+            actions_total: 2,
+            bytes_total: 4,
+            actions_done: 0,
+            ..
+        })
+    ));
+    assert!(matches!(
+        events[3],
+        ProgressEvent::Finished(Progress {
+            actions_done: 2,
+            bytes_done: 4,
+            ..
+        })
+    ));
+}
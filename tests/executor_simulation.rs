@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use assert_cmd::{Command, cargo};
+use elvis::{
+    executor::execution::Executor,
+    planner::{
+        action::{Action, FsObjectKind},
+        mv::MvPlanner,
+        plan::{CommandKind, Plan, PlanMetadata, PlanSummary},
+        traits::Planner,
+    },
+    vfs::memory::MemoryFs,
+};
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+fn plan(actions: Vec<Action>) -> Plan {
+    Plan {
+        metadata: PlanMetadata {
+            command: CommandKind::Script,
+            working_dir: PathBuf::from("/project"),
+            created_at: SystemTime::now(),
+        },
+        actions,
+        warnings: Vec::new(),
+        errors: Vec::new(),
+        summary: PlanSummary::default(),
+        patterns: Vec::new(),
+        skipped: Vec::new(),
+        omitted: 0,
+    }
+}
+
+/// Simulate_DeleteBeforeMove_Fails
+#[test]
+fn simulate_delete_before_move_fails() {
+    let mut fs = MemoryFs::new();
+    fs.add_file("/project/old/a.txt", 3).add_dir("/project/new");
+
+    let simulation = Executor::simulate(
+        &plan(vec![
+            Action::Delete {
+                path: PathBuf::from("/project/old"),
+                kind: FsObjectKind::Directory,
+                recursive: false,
+                size: 0,
+                modified: None,
+            },
+            Action::Move {
+                from: PathBuf::from("/project/old/a.txt"),
+                to: PathBuf::from("/project/new/a.txt"),
+                kind: FsObjectKind::File,
+                overwrite: None,
+                size: 3,
+                modified: None,
+                backup: false,
+            },
+            Action::Create {
+                path: PathBuf::from("/project/missing/b.txt"),
+                kind: FsObjectKind::File,
+            },
+        ]),
+        &fs,
+    );
+
+    let failures: Vec<(usize, &str)> = simulation
+        .failures
+        .iter()
+        .map(|f| (f.index, f.message.as_str()))
+        .collect();
+    assert_eq!(
+        failures,
+        vec![(0, "Directory not empty"), (2, "No such file or directory")]
+    );
+    // The failed delete is skipped, so the move still goes through
+    assert_eq!(simulation.state.len(), 2);
+}
+
+/// Simulate_MvPlan_PredictedState
+#[test]
+fn simulate_mv_plan_predicted_state() {
+    let mut fs = MemoryFs::new();
+    fs.add_file("/project/src/a.txt", 3)
+        .add_file("/project/src/sub/b.txt", 5);
+
+    let plan = MvPlanner::new(
+        vec![PathBuf::from("/project/src")],
+        PathBuf::from("/project/dst"),
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+    let simulation = Executor::simulate(&plan, &fs);
+
+    assert!(simulation.is_ok());
+    let state: Vec<(&Path, Option<FsObjectKind>, u64)> = simulation
+        .state
+        .iter()
+        .map(|e| (e.path.as_path(), e.kind, e.size))
+        .collect();
+    assert_eq!(
+        state,
+        vec![
+            (Path::new("/project/dst"), Some(FsObjectKind::Directory), 0),
+            (Path::new("/project/dst/a.txt"), Some(FsObjectKind::File), 3),
+            (
+                Path::new("/project/dst/sub"),
+                Some(FsObjectKind::Directory),
+                0
+            ),
+            (
+                Path::new("/project/dst/sub/b.txt"),
+                Some(FsObjectKind::File),
+                5
+            ),
+            (Path::new("/project/src"), None, 0),
+            (Path::new("/project/src/a.txt"), None, 0),
+            (Path::new("/project/src/sub"), None, 0),
+            (Path::new("/project/src/sub/b.txt"), None, 0),
+        ]
+    );
+}
+
+/// Simulate_Cli_ReportsStateWithoutExecuting
+///
+/// `elvis --simulate -y mv a.txt b.txt`
+#[test]
+fn simulate_cli_reports_state_without_executing() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "abc").unwrap();
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(dir.path())
+        .args(["--no-color", "--simulate", "-y", "mv", "a.txt", "b.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All 1 actions would succeed"))
+        .stdout(predicate::str::contains("+ b.txt (3 B)"))
+        .stdout(predicate::str::contains("- a.txt"));
+
+    assert!(dir.path().join("a.txt").exists());
+    assert!(!dir.path().join("b.txt").exists());
+}
@@ -0,0 +1,568 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use elvis::{
+    planner::{
+        action::{Action, FsObjectKind},
+        filter::PathFilter,
+        gitignore::GitignoreMode,
+        mv::{BackupMode, MvPlanner, UpdateMode},
+        overlay::Entry,
+        plan::CommandKind,
+        plan::SkipReason,
+        plan::WarningKind,
+        rm::RmPlanner,
+        script::ScriptPlanner,
+        stream::{PlanBuilder, PlanSpool, StreamingPlanner},
+        touch::TouchPlanner,
+        traits::Planner,
+        vcs::RepoState,
+    },
+    printer::stats::PlanStats,
+    vfs::{memory::MemoryFs, real::RealFs, traits::FileSystem},
+};
+
+fn tree() -> MemoryFs {
+    let mut fs = MemoryFs::new();
+    fs.add_file("/project/README.md", 10)
+        .add_file("/project/build/app", 100)
+        .add_file("/project/build/cache/obj.o", 20)
+        .add_dir("/project/dist")
+        .add_symlink("/project/latest", "build");
+    fs
+}
+
+/// MemoryFs_ReadDir_SortedChildren
+#[test]
+fn memory_fs_read_dir_sorted_children() {
+    let fs = tree();
+
+    assert_eq!(
+        fs.read_dir(Path::new("/project")).unwrap(),
+        vec![
+            PathBuf::from("/project/README.md"),
+            PathBuf::from("/project/build"),
+            PathBuf::from("/project/dist"),
+            PathBuf::from("/project/latest"),
+        ]
+    );
+    assert!(fs.is_dir(Path::new("/project/latest")));
+    assert_eq!(
+        fs.symlink_metadata(Path::new("/project/latest"))
+            .unwrap()
+            .kind,
+        FsObjectKind::Symlink
+    );
+}
+
+/// Rm_MemoryFs_Recursive_ContentsFirst
+#[test]
+fn rm_memory_fs_recursive_contents_first() {
+    let fs = tree();
+    let plan = RmPlanner::new(
+        vec![PathBuf::from("/project/build")],
+        true,
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    let deleted: Vec<&Path> = plan
+        .actions
+        .iter()
+        .map(|action| match action {
+            Action::Delete { path, .. } => path.as_path(),
+            other => panic!("unexpected action {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        deleted,
+        vec![
+            Path::new("/project/build/app"),
+            Path::new("/project/build/cache/obj.o"),
+            Path::new("/project/build/cache"),
+            Path::new("/project/build"),
+        ]
+    );
+    assert_eq!(plan.summary.files_deleted, 2);
+    assert_eq!(plan.summary.dirs_deleted, 2);
+    assert_eq!(plan.summary.bytes_deleted, 120);
+}
+
+/// Rm_MemoryFs_Missing_Error
+#[test]
+fn rm_memory_fs_missing_error() {
+    let fs = tree();
+    let plan = RmPlanner::new(
+        vec![
+            PathBuf::from("/project/nope"),
+            PathBuf::from("/project/dist"),
+        ],
+        false,
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    assert_eq!(plan.errors.len(), 1);
+    assert_eq!(plan.summary.dirs_deleted, 1);
+}
+
+/// Rm_MemoryFs_RespectGitignore_ReadsIgnoreFilesFromVfs
+#[test]
+fn rm_memory_fs_respect_gitignore_reads_ignore_files_from_vfs() {
+    let mut fs = tree();
+    fs.add_text_file("/project/build/.gitignore", "cache/\n*.o\n");
+    let plan = RmPlanner::new(
+        vec![PathBuf::from("/project/build")],
+        true,
+        false,
+        PathBuf::from("/project"),
+    )
+    .with_filter(
+        PathFilter::new(&[], &[])
+            .unwrap()
+            .with_gitignore(GitignoreMode::Respect),
+    )
+    .plan(&fs);
+
+    let deleted: Vec<&Path> = plan
+        .actions
+        .iter()
+        .map(|action| action.paths()[0])
+        .collect();
+    assert_eq!(
+        deleted,
+        vec![
+            Path::new("/project/build/.gitignore"),
+            Path::new("/project/build/app"),
+        ]
+    );
+}
+
+/// Rm_MemoryFs_Repo_TrackedAndChangedWarnings
+#[test]
+fn rm_memory_fs_repo_tracked_and_changed_warnings() {
+    let mut fs = tree();
+    fs.add_repo(
+        "/project",
+        RepoState::parse("README.md\0build/app\0", " M build/app\0"),
+    );
+    let plan = RmPlanner::new(
+        vec![PathBuf::from("/project/README.md")],
+        false,
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+    assert_eq!(plan.warnings.len(), 1);
+    assert!(matches!(plan.warnings[0].kind, WarningKind::VcsTracked));
+    assert_eq!(
+        plan.warnings[0].paths,
+        vec![PathBuf::from("/project/README.md")]
+    );
+
+    let plan = RmPlanner::new(
+        vec![PathBuf::from("/project/build/app")],
+        false,
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+    assert_eq!(plan.warnings.len(), 1);
+    assert!(matches!(
+        plan.warnings[0].kind,
+        WarningKind::UncommittedChanges
+    ));
+}
+
+/// Rm_MemoryFs_InsideHostRepo_NoVcsWarnings
+///
+/// The tree is mounted under this crate's own checkout, which is a git
+/// repository on the host; none of it may leak into the plan.
+#[test]
+fn rm_memory_fs_inside_host_repo_no_vcs_warnings() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut fs = MemoryFs::new();
+    fs.add_file(root.join("Cargo.toml"), 10);
+    let plan = RmPlanner::new(
+        vec![root.join("Cargo.toml")],
+        false,
+        false,
+        root.to_path_buf(),
+    )
+    .plan(&fs);
+
+    assert!(plan.errors.is_empty());
+    assert!(plan.warnings.is_empty());
+}
+
+/// Rm_MemoryFs_Spool_PreviewAndReadBack
+#[test]
+fn rm_memory_fs_spool_preview_and_read_back() {
+    let fs = tree();
+    let planner = RmPlanner::new(
+        vec![PathBuf::from("/project/build")],
+        true,
+        false,
+        PathBuf::from("/project"),
+    );
+    let spool = PlanSpool::collect(
+        PlanBuilder::new(&fs, CommandKind::Rm, PathBuf::from("/project")),
+        planner.stream(&fs),
+        1,
+    )
+    .unwrap();
+
+    assert_eq!(spool.plan.actions.len(), 1);
+    assert_eq!(spool.plan.omitted, 3);
+    assert_eq!(spool.actions_total, 4);
+    assert_eq!(spool.bytes_total, 120);
+    assert_eq!(spool.plan.summary.files_deleted, 2);
+
+    let spooled: Vec<Action> = spool.actions().unwrap().map(Result::unwrap).collect();
+    let planned = planner.plan(&fs).actions;
+    assert_eq!(
+        spooled.iter().map(Action::paths).collect::<Vec<_>>(),
+        planned.iter().map(Action::paths).collect::<Vec<_>>()
+    );
+}
+
+/// Rm_MemoryFs_Spool_VcsWarningPreview
+#[test]
+fn rm_memory_fs_spool_vcs_warning_preview() {
+    let mut fs = tree();
+    fs.add_repo(
+        "/project",
+        RepoState::parse("build/app\0build/cache/obj.o\0", ""),
+    );
+    let planner = RmPlanner::new(
+        vec![PathBuf::from("/project/build")],
+        true,
+        false,
+        PathBuf::from("/project"),
+    );
+    let spool = PlanSpool::collect(
+        PlanBuilder::new(&fs, CommandKind::Rm, PathBuf::from("/project")),
+        planner.stream(&fs),
+        1,
+    )
+    .unwrap();
+
+    let warning = spool
+        .plan
+        .warnings
+        .iter()
+        .find(|w| matches!(w.kind, WarningKind::VcsTracked))
+        .unwrap();
+    assert_eq!(warning.message, "2 files tracked by git will be removed");
+    assert_eq!(warning.paths, vec![PathBuf::from("/project/build/app")]);
+}
+
+/// Mv_MemoryFs_IntoDirectory_Overwrite
+#[test]
+fn mv_memory_fs_into_directory_overwrite() {
+    let mut fs = tree();
+    fs.add_file("/project/dist/README.md", 4);
+    let plan = MvPlanner::new(
+        vec![PathBuf::from("/project/README.md")],
+        PathBuf::from("/project/dist"),
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    assert!(plan.errors.is_empty());
+    assert!(matches!(
+        &plan.actions[..],
+        [Action::Move { to, overwrite: Some(Entry { kind: FsObjectKind::File, size: 4 }), size: 10, .. }]
+            if to == Path::new("/project/dist/README.md")
+    ));
+    assert_eq!(plan.summary.bytes_overwritten, 4);
+}
+
+/// Mv_MemoryFs_FileOntoDirectory_Error
+#[test]
+fn mv_memory_fs_file_onto_directory_error() {
+    let mut fs = tree();
+    fs.add_file("/project/dist/keep", 1)
+        .add_file("/project/dist/README.md/nested", 2);
+
+    // `mv -T README.md dist` and `mv README.md dist` with dist/README.md/
+    for (no_target_directory, to) in [(true, "/project/dist"), (false, "/project/dist/README.md")] {
+        let plan = MvPlanner::new(
+            vec![PathBuf::from("/project/README.md")],
+            PathBuf::from("/project/dist"),
+            false,
+            PathBuf::from("/project"),
+        )
+        .with_no_target_directory(no_target_directory)
+        .plan(&fs);
+
+        assert!(plan.actions.is_empty());
+        assert_eq!(plan.errors.len(), 1);
+        assert_eq!(plan.errors[0].path.as_deref(), Some(Path::new(to)));
+        assert_eq!(
+            plan.errors[0].message,
+            "Cannot overwrite directory with non-directory"
+        );
+    }
+}
+
+/// Mv_MemoryFs_BackupOntoDirectory_Error
+#[test]
+fn mv_memory_fs_backup_onto_directory_error() {
+    let mut fs = tree();
+    fs.add_file("/project/dist/README.md", 4)
+        .add_file("/project/dist/README.md~/old", 7);
+    let plan = MvPlanner::new(
+        vec![PathBuf::from("/project/README.md")],
+        PathBuf::from("/project/dist"),
+        false,
+        PathBuf::from("/project"),
+    )
+    .with_backup(BackupMode::Simple, "~".into())
+    .plan(&fs);
+
+    // A directory in the way is refused instead of counted as 0 B
+    assert!(plan.actions.is_empty());
+    assert_eq!(
+        plan.errors[0].path.as_deref(),
+        Some(Path::new("/project/dist/README.md~"))
+    );
+    assert_eq!(plan.summary.bytes_overwritten, 0);
+}
+
+/// Mv_MemoryFs_BackupExisting_NumberedPerDestination
+#[test]
+fn mv_memory_fs_backup_existing_numbered_per_destination() {
+    let mut fs = tree();
+    fs.add_file("/project/src/README.md", 3)
+        .add_file("/project/src/app", 5)
+        .add_file("/project/dist/README.md", 4)
+        .add_file("/project/dist/README.md.~2~", 1)
+        .add_file("/project/dist/app", 6);
+    let plan = MvPlanner::new(
+        vec![
+            PathBuf::from("/project/src/README.md"),
+            PathBuf::from("/project/src/app"),
+        ],
+        PathBuf::from("/project/dist"),
+        false,
+        PathBuf::from("/project"),
+    )
+    .with_backup(BackupMode::Existing, "~".into())
+    .plan(&fs);
+
+    let moves: Vec<(&Path, &Path, bool)> = plan
+        .actions
+        .iter()
+        .map(|action| match action {
+            Action::Move {
+                from,
+                to,
+                overwrite,
+                ..
+            } => (from.as_path(), to.as_path(), overwrite.is_some()),
+            other => panic!("unexpected action {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        moves,
+        vec![
+            (
+                Path::new("/project/dist/README.md"),
+                Path::new("/project/dist/README.md.~3~"),
+                false
+            ),
+            (
+                Path::new("/project/src/README.md"),
+                Path::new("/project/dist/README.md"),
+                false
+            ),
+            (
+                Path::new("/project/dist/app"),
+                Path::new("/project/dist/app~"),
+                false
+            ),
+            (
+                Path::new("/project/src/app"),
+                Path::new("/project/dist/app"),
+                false
+            ),
+        ]
+    );
+    assert!(plan.warnings.is_empty());
+    assert_eq!(plan.summary.bytes_overwritten, 0);
+}
+
+/// Stats_MemoryFsPlan_UsesRecordedTimes
+#[test]
+fn stats_memory_fs_plan_uses_recorded_times() {
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let mut fs = tree();
+    fs.set_modified(Path::new("/project/build/app"), old)
+        .set_modified(
+            Path::new("/project/build/cache/obj.o"),
+            old + Duration::from_secs(60),
+        );
+    let plan = RmPlanner::new(
+        vec![PathBuf::from("/project/build")],
+        true,
+        false,
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    // None of these paths exist on disk, so the times come from the plan
+    let stats = PlanStats::from_plan(&plan);
+    let oldest = stats.oldest.unwrap();
+    assert_eq!(oldest.path, Path::new("/project/build/app"));
+    assert_eq!(oldest.mtime, Some(1_000));
+    assert_eq!(stats.newest.unwrap().mtime, Some(1_060));
+}
+
+/// Mv_MemoryFs_UpdateOlder_SkipsNewerAndKeepsSource
+#[test]
+fn mv_memory_fs_update_older_skips_newer_and_keeps_source() {
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let new = old + Duration::from_secs(60);
+    let mut fs = tree();
+    fs.add_file("/project/src/stale.txt", 3)
+        .add_file("/project/src/fresh.txt", 3)
+        .add_file("/project/dist/src/stale.txt", 2)
+        .add_file("/project/dist/src/fresh.txt", 2)
+        .set_modified(Path::new("/project/src/stale.txt"), old)
+        .set_modified(Path::new("/project/dist/src/stale.txt"), new)
+        .set_modified(Path::new("/project/src/fresh.txt"), new)
+        .set_modified(Path::new("/project/dist/src/fresh.txt"), old);
+    let plan = MvPlanner::new(
+        vec![PathBuf::from("/project/src")],
+        PathBuf::from("/project/dist"),
+        false,
+        PathBuf::from("/project"),
+    )
+    .with_update(UpdateMode::Older)
+    .plan(&fs);
+
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].path, Path::new("/project/src/stale.txt"));
+    assert_eq!(plan.skipped[0].reason, SkipReason::DestinationNotOlder);
+    assert_eq!(plan.summary.files_skipped, 1);
+    assert_eq!(plan.summary.files_moved, 1);
+    assert_eq!(plan.summary.bytes_overwritten, 2);
+    // The source directory still holds the skipped file
+    assert!(
+        !plan
+            .actions
+            .iter()
+            .any(|action| matches!(action, Action::Delete { .. }))
+    );
+}
+
+/// Touch_MemoryFs_CreateAndModify
+#[test]
+fn touch_memory_fs_create_and_modify() {
+    let fs = tree();
+    let plan = TouchPlanner::new(
+        vec![
+            PathBuf::from("/project/README.md"),
+            PathBuf::from("/project/NEW"),
+        ],
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    assert_eq!(plan.summary.files_modified, 1);
+    assert_eq!(plan.summary.files_created, 1);
+}
+
+/// Script_MemoryFs_ReplaysAgainstTree
+#[test]
+fn script_memory_fs_replays_against_tree() {
+    let fs = tree();
+    let plan = ScriptPlanner::new(
+        "mv build/app dist/\nrm -r build\n".into(),
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    assert!(plan.errors.is_empty());
+    assert_eq!(plan.summary.files_moved, 1);
+    assert_eq!(plan.summary.files_deleted, 1);
+    assert_eq!(plan.summary.dirs_deleted, 2);
+}
+
+/// Script_AttachedOperators_Unsupported
+#[test]
+fn script_attached_operators_unsupported() {
+    let fs = tree();
+
+    for (source, op) in [
+        ("rm -rf build>/dev/null", ">"),
+        ("mv README.md b&&rm b", "&&"),
+    ] {
+        let plan = ScriptPlanner::new(source.into(), PathBuf::from("/project")).plan(&fs);
+
+        assert!(plan.actions.is_empty(), "{source}");
+        assert_eq!(plan.errors.len(), 1, "{source}");
+        assert_eq!(
+            plan.errors[0].message,
+            format!("line 1: `{op}` is not supported")
+        );
+    }
+
+    let plan = ScriptPlanner::new(
+        "touch 'a>b' \\&c; touch d\n".into(),
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+    assert!(plan.errors.is_empty());
+    assert_eq!(plan.summary.files_created, 3);
+}
+
+/// Script_MkdirUnderFile_NotADirectory
+#[test]
+fn script_mkdir_under_file_not_a_directory() {
+    let fs = tree();
+    let plan = ScriptPlanner::new(
+        "mkdir README.md/sub\nmkdir -p README.md/a/b\n".into(),
+        PathBuf::from("/project"),
+    )
+    .plan(&fs);
+
+    assert!(plan.actions.is_empty());
+    assert_eq!(plan.errors.len(), 2);
+    assert_eq!(plan.errors[0].message, "line 1: mkdir: Not a directory");
+    assert_eq!(plan.errors[1].message, "line 2: mkdir: Not a directory");
+}
+
+/// RealFs_ParallelWalk_SameOrderAsSequential
+#[test]
+fn real_fs_parallel_walk_same_order_as_sequential() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("tree");
+    for path in ["a/b/c.txt", "a/b.txt", "a.txt", "b/a/z", "b/a.d/y", "b/a-x"] {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "x").unwrap();
+    }
+
+    let sequential = RealFs::default();
+    let parallel = RealFs { threads: 4 };
+    for contents_first in [false, true] {
+        let expected: Vec<PathBuf> = sequential
+            .walk(&root, contents_first)
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        let found: Vec<PathBuf> = parallel
+            .walk(&root, contents_first)
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        assert_eq!(found, expected);
+    }
+}